@@ -35,6 +35,7 @@ impl Webhook for SlackAlerter {
       Some(Event { status: OK, .. }) => ("(ok)", COLOR_OK),
       Some(Event { status: CRITICAL, .. }) => ("(critical)", COLOR_CRITICAL),
       Some(Event { status: WARNING, .. }) => ("(warning)", COLOR_WARNING),
+      Some(Event { status: UNKNOWN, .. }) => ("(unknown)", COLOR_UNKNOWN),
       _ => ("", COLOR_UNKNOWN),
     };
 
@@ -34,10 +34,11 @@ impl Webhook for WebhookAlerter {
       Ok(Some(Event { status: OK, .. })) => Some("ok"),
       Ok(Some(Event { status: CRITICAL, .. })) => Some("critical"),
       Ok(Some(Event { status: WARNING, .. })) => Some("warning"),
+      Ok(Some(Event { status: UNKNOWN, .. })) => Some("unknown"),
       _ => None,
     };
 
-    let spec = check.spec(conn).await?;
+    let spec = check.spec(conn).await?.redacted();
     let payload = Payload { level, check, spec, outage };
 
     let request = ureq::post(url);
@@ -58,3 +59,56 @@ impl Webhook for WebhookAlerter {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::mpsc;
+
+  use anyhow::Result;
+
+  use super::WebhookAlerter;
+  use crate::{
+    alerters::Webhook,
+    handlers::testing,
+    model::{Alerter, Check, Outage},
+    tests,
+  };
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn webhook_redacts_spec() -> Result<()> {
+    let pool = tests::db_client().await?;
+
+    pool.create_check(None, None, "webhook_redacts_spec()", None, None).await?;
+    pool.create_database_spec(1).await?;
+    pool.create_resolved_outage(None, None).await?;
+
+    let (tx, rx) = mpsc::channel();
+
+    let addr = testing::serve(move |request| {
+      let _ = tx.send(request.body.clone());
+
+      ("200 OK", "")
+    })
+    .await?;
+
+    let mut conn = pool.acquire().await?;
+    let check = Check::by_id(&mut conn, 1).await?;
+    let outage = Outage::by_uuid(&mut conn, "dd9a531a-1b0b-4a12-bc09-e5637f916261").await?;
+
+    let alerter = WebhookAlerter(Alerter {
+      url: Some(format!("http://{addr}/")),
+      ..Default::default()
+    });
+
+    alerter.alert(tests::config(false), &mut conn, &check, &outage).await?;
+
+    let body = rx.recv()?;
+
+    assert!(body.contains(r#""kind":"database""#));
+    assert!(!body.contains("secret"));
+
+    pool.cleanup().await;
+
+    Ok(())
+  }
+}
@@ -53,7 +53,7 @@ pub async fn list(_: Auth, pool: State<Pool<MySql>>, Query(ListQuery { all, grou
     .context("could not retrieve checks")
     .short()?;
 
-  Ok(Json(checks))
+  Ok(Json(checks.into_iter().map(api::Check::redacted).collect()))
 }
 
 pub async fn get(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>) -> ApiResponse<Json<api::Check>> {
@@ -61,7 +61,7 @@ pub async fn get(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>) ->
   let mut conn = pool.acquire().await.context("could not retrieve database connection").short()?;
   let check = Check::by_uuid(&mut conn, &uuid).await.context("could not retrieve check").short()?.map(pool).await.short()?;
 
-  Ok(Json(check))
+  Ok(Json(check.redacted()))
 }
 
 pub async fn create(_: Auth, config: State<Arc<Config>>, pool: State<Pool<MySql>>, payload: Result<Json<api::Check>, JsonRejection>) -> ApiResponse<impl IntoResponse> {
@@ -77,6 +77,9 @@ pub async fn create(_: Auth, config: State<Arc<Config>>, pool: State<Pool<MySql>
     Err(anyhow!("`site_threshold` cannot exceed the number of `sites`")).context(AppError::BadRequest).short()?;
   }
 
+  check_handler(&config, &payload.spec).short()?;
+  payload.spec.validate().context(AppError::BadRequest).short()?;
+
   let mut txn = pool.begin().await.context("could not start transaction").short()?;
 
   let group = match payload.group_in {
@@ -118,7 +121,7 @@ pub async fn create(_: Auth, config: State<Arc<Config>>, pool: State<Pool<MySql>
   Ok((StatusCode::CREATED, [(header::LOCATION, format!("/api/checks/{}", check.uuid))]))
 }
 
-pub async fn update(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>, payload: Result<Json<api::Check>, JsonRejection>) -> ApiResponse<()> {
+pub async fn update(_: Auth, config: State<Arc<Config>>, pool: State<Pool<MySql>>, Path(uuid): Path<String>, payload: Result<Json<api::Check>, JsonRejection>) -> ApiResponse<()> {
   let payload = check_json(payload).short()?;
 
   let sites = match payload.sites {
@@ -130,6 +133,9 @@ pub async fn update(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>,
     Err(anyhow!("`site_threshold` cannot exceed the number of `sites`").context(AppError::BadRequest)).short()?;
   }
 
+  check_handler(&config, &payload.spec).short()?;
+  payload.spec.validate().context(AppError::BadRequest).short()?;
+
   let mut txn = pool.begin().await.context("could not start transaction").short()?;
   let check = Check::by_uuid(&mut txn, &uuid).await.context("could not retrieve check").short()?;
 
@@ -137,6 +143,14 @@ pub async fn update(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>,
     Err(anyhow!("cannot change the resource `kind`").context(AppError::BadRequest)).short()?;
   }
 
+  if let api::Spec::External(ref spec) = payload.spec {
+    let current = check.spec(&mut txn).await.context("could not retrieve spec").short()?;
+
+    if !matches!(current, api::Spec::External(current) if current.handler == spec.handler) {
+      Err(anyhow!("cannot change the resource `kind`").context(AppError::BadRequest)).short()?;
+    }
+  }
+
   let group = match payload.group_in {
     Some(group) => Some(Group::by_uuid(&mut txn, &group).await.context("could not retrieve group").short()?),
     None => None,
@@ -171,9 +185,14 @@ pub async fn update(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>,
   Ok(())
 }
 
-pub async fn patch(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>, payload: Result<Json<api::CheckPatch>, JsonRejection>) -> ApiResponse<()> {
+pub async fn patch(_: Auth, config: State<Arc<Config>>, pool: State<Pool<MySql>>, Path(uuid): Path<String>, payload: Result<Json<api::CheckPatch>, JsonRejection>) -> ApiResponse<()> {
   let payload = check_json(payload).short()?;
 
+  if let Some(ref spec) = payload.spec {
+    check_handler(&config, spec).short()?;
+    spec.validate().context(AppError::BadRequest).short()?;
+  }
+
   let mut txn = pool.begin().await.context("could not start transaction").short()?;
   let mut check = Check::by_uuid(&mut txn, &uuid).await.context("could not retrieve check").short()?;
 
@@ -229,6 +248,14 @@ pub async fn patch(_: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<String>,
   Ok(())
 }
 
+/// Refuse specs meant for an external handler that is not registered on the controller.
+fn check_handler(config: &Config, spec: &api::Spec) -> anyhow::Result<()> {
+  match spec {
+    api::Spec::External(spec) if !config.checks.handlers.contains(&spec.handler) => Err(anyhow!("unknown handler `{}`", spec.handler).context(AppError::BadRequest)),
+    _ => Ok(()),
+  }
+}
+
 #[derive(Deserialize)]
 pub struct DeleteQuery {
   delete: Option<bool>,
@@ -450,6 +477,54 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  async fn create_redacts_database_dsn() -> Result<()> {
+    let (pool, client) = tests::api_client().await?;
+
+    let check = json!({
+      "name": "create_redacts_database_dsn()",
+      "enabled": false,
+      "interval": "10s",
+      "sites": [CONTROLLER_ID],
+      "site_threshold": 1,
+      "passing_threshold": 1,
+      "failing_threshold": 1,
+      "spec": {
+        "kind": "database",
+        "engine": "redis",
+        "dsn": "redis://:secret@localhost/"
+      }
+    });
+
+    let response = client
+      .clone()
+      .oneshot(
+        Request::builder()
+          .method(Method::POST)
+          .uri("/api/checks")
+          .header("content-type", "application/json")
+          .body(Body::from(check.to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = client.oneshot(Request::builder().uri("/api/checks").body(Body::empty()).unwrap()).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+
+    assert!(!String::from_utf8_lossy(&body).contains("secret"));
+
+    let spec = sqlx::query_as::<_, (String,)>("SELECT dsn FROM database_specs").fetch_one(&*pool).await?;
+
+    assert_eq!(&spec.0, "redis://:secret@localhost/");
+
+    pool.cleanup().await;
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn create_invalid_kind() -> Result<()> {
     let (pool, client) = tests::api_client().await?;
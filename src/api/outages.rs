@@ -137,3 +137,42 @@ pub async fn comment(auth: Auth, pool: State<Pool<MySql>>, Path(uuid): Path<Stri
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use anyhow::Result;
+  use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+  };
+  use http_body_util::BodyExt;
+  use tower::ServiceExt;
+
+  use crate::tests;
+
+  #[tokio::test]
+  async fn list_redacts_spec() -> Result<()> {
+    let (pool, client) = tests::api_client().await?;
+
+    pool.create_check(None, None, "list_redacts_spec()", None, None).await?;
+    pool.create_database_spec(1).await?;
+    pool.create_resolved_outage(None, None).await?;
+
+    let response = client
+      .oneshot(Request::builder().uri("/api/outages?from=2020-12-31T00:00:00&to=2100-12-31T00:00:00").body(Body::empty()).unwrap())
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8_lossy(&body);
+
+    assert!(body.contains(r#""kind":"database""#));
+    assert!(!body.contains("secret"));
+
+    pool.cleanup().await;
+
+    Ok(())
+  }
+}
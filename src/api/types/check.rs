@@ -50,6 +50,12 @@ pub struct Check {
   pub sites: Option<api::Sites>,
 }
 
+impl Check {
+  pub fn redacted(self) -> Check {
+    Check { spec: self.spec.redacted(), ..self }
+  }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CheckGroup {
   pub uuid: String,
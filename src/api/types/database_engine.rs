@@ -0,0 +1,50 @@
+use std::{
+  convert::TryFrom,
+  fmt::{self, Formatter},
+};
+
+use crate::model::specs::DatabaseEngine;
+
+use serde::{de, ser};
+
+impl ser::Serialize for DatabaseEngine {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: ser::Serializer,
+  {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+struct DatabaseEngineVisitor;
+
+impl de::Visitor<'_> for DatabaseEngineVisitor {
+  type Value = DatabaseEngine;
+
+  fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+    formatter.write_str("a string representing a database engine")
+  }
+
+  fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    DatabaseEngine::try_from(value.to_owned()).map_err(de::Error::custom)
+  }
+
+  fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    DatabaseEngine::try_from(value).map_err(de::Error::custom)
+  }
+}
+
+impl<'de> de::Deserialize<'de> for DatabaseEngine {
+  fn deserialize<D>(deserializer: D) -> Result<DatabaseEngine, D::Error>
+  where
+    D: de::Deserializer<'de>,
+  {
+    deserializer.deserialize_string(DatabaseEngineVisitor)
+  }
+}
@@ -0,0 +1,50 @@
+use std::{
+  convert::TryFrom,
+  fmt::{self, Formatter},
+};
+
+use crate::model::specs::DnsTransport;
+
+use serde::{de, ser};
+
+impl ser::Serialize for DnsTransport {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: ser::Serializer,
+  {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+struct DnsTransportVisitor;
+
+impl de::Visitor<'_> for DnsTransportVisitor {
+  type Value = DnsTransport;
+
+  fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+    formatter.write_str("a string representing a DNS transport")
+  }
+
+  fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    DnsTransport::try_from(value.to_owned()).map_err(de::Error::custom)
+  }
+
+  fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    DnsTransport::try_from(value).map_err(de::Error::custom)
+  }
+}
+
+impl<'de> de::Deserialize<'de> for DnsTransport {
+  fn deserialize<D>(deserializer: D) -> Result<DnsTransport, D::Error>
+  where
+    D: de::Deserializer<'de>,
+  {
+    deserializer.deserialize_string(DnsTransportVisitor)
+  }
+}
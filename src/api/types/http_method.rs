@@ -0,0 +1,50 @@
+use std::{
+  convert::TryFrom,
+  fmt::{self, Formatter},
+};
+
+use crate::model::specs::HttpMethod;
+
+use serde::{de, ser};
+
+impl ser::Serialize for HttpMethod {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: ser::Serializer,
+  {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+struct HttpMethodVisitor;
+
+impl de::Visitor<'_> for HttpMethodVisitor {
+  type Value = HttpMethod;
+
+  fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+    formatter.write_str("a string representing an HTTP method")
+  }
+
+  fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    HttpMethod::try_from(value.to_owned()).map_err(de::Error::custom)
+  }
+
+  fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    HttpMethod::try_from(value).map_err(de::Error::custom)
+  }
+}
+
+impl<'de> de::Deserialize<'de> for HttpMethod {
+  fn deserialize<D>(deserializer: D) -> Result<HttpMethod, D::Error>
+  where
+    D: de::Deserializer<'de>,
+  {
+    deserializer.deserialize_string(HttpMethodVisitor)
+  }
+}
@@ -0,0 +1,50 @@
+use std::{
+  convert::TryFrom,
+  fmt::{self, Formatter},
+};
+
+use crate::model::specs::MailProtocol;
+
+use serde::{de, ser};
+
+impl ser::Serialize for MailProtocol {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: ser::Serializer,
+  {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+struct MailProtocolVisitor;
+
+impl de::Visitor<'_> for MailProtocolVisitor {
+  type Value = MailProtocol;
+
+  fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+    formatter.write_str("a string representing a mail access protocol")
+  }
+
+  fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    MailProtocol::try_from(value.to_owned()).map_err(de::Error::custom)
+  }
+
+  fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    MailProtocol::try_from(value).map_err(de::Error::custom)
+  }
+}
+
+impl<'de> de::Deserialize<'de> for MailProtocol {
+  fn deserialize<D>(deserializer: D) -> Result<MailProtocol, D::Error>
+  where
+    D: de::Deserializer<'de>,
+  {
+    deserializer.deserialize_string(MailProtocolVisitor)
+  }
+}
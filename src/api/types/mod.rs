@@ -2,15 +2,22 @@ mod alerter_kind;
 mod binary;
 mod check;
 mod check_kind;
+mod database_engine;
 mod date;
+mod dns_matching;
 mod dns_record;
+mod dns_transport;
 mod duration;
+mod http_method;
+mod mail_protocol;
+mod mail_security;
 mod outage;
 mod query;
 mod report;
 mod site;
 mod site_outage;
 mod spec;
+mod starttls_protocol;
 mod status;
 mod timeline;
 mod user;
@@ -21,7 +21,7 @@ impl ApiMapper for db::SiteOutage {
   async fn map(self, pool: &Pool<MySql>) -> Result<Self::Output> {
     let mut conn = pool.acquire().await.context("could not retrieve database connection")?;
     let check = db::Check::by_id(&mut conn, self.check_id).await?;
-    let spec = check.spec(&mut conn).await?;
+    let spec = check.spec(&mut conn).await?.redacted();
     let group = check.group(&mut conn).await;
     let alerter = check.alerter(&mut conn).await;
     let sites = check.sites(&mut conn).await?;
@@ -53,7 +53,7 @@ impl ApiMapper for Vec<db::SiteOutage> {
       .then(async move |outage| {
         if let Ok(mut conn) = pool.acquire().await.context("could not retrieve database connection") {
           match db::Check::by_id(&mut conn, outage.check_id).await {
-            Ok(check) => match check.spec(&mut conn).await {
+            Ok(check) => match check.spec(&mut conn).await.map(api::Spec::redacted) {
               Ok(spec) => {
                 let group = check.group(&mut conn).await;
                 let alerter = check.alerter(&mut conn).await;
@@ -1,16 +1,20 @@
 use anyhow::Result;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 use sqlx::MySqlConnection;
 
 use crate::{
   api::types::Spec as api,
+  config,
   model::{
     specs::{self as db, SpecMeta},
     Check as DbCheck, CheckKind,
   },
 };
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "kind")]
+#[serde(remote = "Self", tag = "kind")]
 pub enum Spec {
   #[cfg(feature = "ping")]
   #[serde(rename = "ping")]
@@ -25,6 +29,39 @@ pub enum Spec {
   Udp(db::Udp),
   #[serde(rename = "tls")]
   Tls(db::Tls),
+  #[serde(rename = "smtp")]
+  Smtp(db::Smtp),
+  #[serde(rename = "mail_access")]
+  MailAccess(db::MailAccess),
+  #[serde(rename = "ssh")]
+  Ssh(db::Ssh),
+  #[serde(rename = "grpc")]
+  Grpc(db::Grpc),
+  #[serde(rename = "websocket")]
+  WebSocket(db::WebSocket),
+  #[serde(rename = "database")]
+  Database(db::Database),
+  #[serde(rename = "ntp")]
+  Ntp(db::Ntp),
+  #[serde(rename = "dns_consistency")]
+  DnsConsistency(db::DnsConsistency),
+  #[serde(rename = "dnssec")]
+  Dnssec(db::Dnssec),
+  #[serde(rename = "http_flow")]
+  HttpFlow(db::HttpFlow),
+  #[serde(rename = "security_headers")]
+  SecurityHeaders(db::SecurityHeaders),
+  #[serde(rename = "crawl")]
+  Crawl(db::Crawl),
+  #[serde(rename = "prometheus")]
+  Prometheus(db::Prometheus),
+  #[serde(rename = "exec")]
+  Exec(db::Exec),
+  #[serde(rename = "script")]
+  Script(db::Script),
+  #[serde(rename = "wasm")]
+  #[cfg(feature = "wasm")]
+  Wasm(db::Wasm),
   #[serde(rename = "play_store")]
   PlayStore(db::PlayStore),
   #[serde(rename = "app_store")]
@@ -36,10 +73,35 @@ pub enum Spec {
   Python(db::Python),
   #[serde(rename = "deadmanswitch")]
   DeadManSwitch(db::DeadManSwitch),
+  #[serde(skip)]
+  External(db::External),
   #[serde(rename = "unsupported")]
   Unsupported,
 }
 
+impl Serialize for Spec {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      api::External(spec) => spec.serialize(serializer),
+      spec => Spec::serialize(spec, serializer),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for Spec {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Spec, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+
+    // Kinds that are not built into defcon are handed to the external handler registered under that name, any other
+    // kind is left to the builtin variants so unknown kinds are still rejected.
+    match value.get("kind").and_then(Value::as_str) {
+      Some(kind) if CheckKind::try_from(kind.to_string()).is_err() && config::is_external_handler(kind) => db::External::deserialize(value).map(api::External),
+      _ => Spec::deserialize(value),
+    }
+    .map_err(de::Error::custom)
+  }
+}
+
 impl Spec {
   pub fn kind(&'_ self) -> CheckKind {
     use CheckKind::*;
@@ -52,16 +114,69 @@ impl Spec {
       api::Tcp(_) => Tcp,
       api::Udp(_) => Udp,
       api::Tls(_) => Tls,
+      api::Smtp(_) => Smtp,
+      api::MailAccess(_) => MailAccess,
+      api::Ssh(_) => Ssh,
+      api::Grpc(_) => Grpc,
+      api::WebSocket(_) => WebSocket,
+      api::Database(_) => Database,
+      api::Ntp(_) => Ntp,
+      api::DnsConsistency(_) => DnsConsistency,
+      api::Dnssec(_) => Dnssec,
+      api::HttpFlow(_) => HttpFlow,
+      api::SecurityHeaders(_) => SecurityHeaders,
+      api::Crawl(_) => Crawl,
+      api::Prometheus(_) => Prometheus,
+      api::Exec(_) => Exec,
+      api::Script(_) => Script,
+      #[cfg(feature = "wasm")]
+      api::Wasm(_) => Wasm,
       api::PlayStore(_) => PlayStore,
       api::AppStore(_) => AppStore,
       api::Whois(_) => Whois,
       #[cfg(feature = "python")]
       api::Python(_) => Python,
       api::DeadManSwitch(_) => DeadManSwitch,
+      api::External(_) => External,
       api::Unsupported => Unsupported,
     }
   }
 
+  /// Strip secrets from the spec before it is returned to API users.
+  pub fn redacted(self) -> Spec {
+    match self {
+      api::Http(spec) => api::Http(db::Http {
+        password: None,
+        token: None,
+        client_key: None,
+        ..spec
+      }),
+      api::MailAccess(spec) => api::MailAccess(db::MailAccess { password: None, ..spec }),
+      api::HttpFlow(mut spec) => {
+        for step in spec.steps.0.iter_mut() {
+          step.request.password = None;
+          step.request.token = None;
+          step.request.client_key = None;
+        }
+
+        api::HttpFlow(spec)
+      }
+      api::Database(spec) => api::Database(db::Database { dsn: None, ..spec }),
+      spec => spec,
+    }
+  }
+
+  /// Reject specs whose attributes contradict each other.
+  pub fn validate(&self) -> Result<()> {
+    match self {
+      api::Http(spec) => spec.validate(),
+      api::HttpFlow(spec) => spec.steps.iter().try_for_each(|step| step.request.validate()),
+      api::MailAccess(spec) => spec.validate(),
+      api::SecurityHeaders(spec) => spec.validate(),
+      _ => Ok(()),
+    }
+  }
+
   pub fn meta(&'_ self) -> &'_ dyn SpecMeta {
     match self {
       #[cfg(feature = "ping")]
@@ -71,12 +186,30 @@ impl Spec {
       api::Tcp(spec) => spec,
       api::Udp(spec) => spec,
       api::Tls(spec) => spec,
+      api::Smtp(spec) => spec,
+      api::MailAccess(spec) => spec,
+      api::Ssh(spec) => spec,
+      api::Grpc(spec) => spec,
+      api::WebSocket(spec) => spec,
+      api::Database(spec) => spec,
+      api::Ntp(spec) => spec,
+      api::DnsConsistency(spec) => spec,
+      api::Dnssec(spec) => spec,
+      api::HttpFlow(spec) => spec,
+      api::SecurityHeaders(spec) => spec,
+      api::Crawl(spec) => spec,
+      api::Prometheus(spec) => spec,
+      api::Exec(spec) => spec,
+      api::Script(spec) => spec,
+      #[cfg(feature = "wasm")]
+      api::Wasm(spec) => spec,
       api::PlayStore(spec) => spec,
       api::AppStore(spec) => spec,
       api::Whois(spec) => spec,
       #[cfg(feature = "python")]
       api::Python(spec) => spec,
       api::DeadManSwitch(spec) => spec,
+      api::External(spec) => spec,
       api::Unsupported => &db::Unsupported,
     }
   }
@@ -90,12 +223,30 @@ impl Spec {
       api::Tcp(spec) => db::Tcp::insert(pool, check, spec).await,
       api::Udp(spec) => db::Udp::insert(pool, check, spec).await,
       api::Tls(spec) => db::Tls::insert(pool, check, spec).await,
+      api::Smtp(spec) => db::Smtp::insert(pool, check, spec).await,
+      api::MailAccess(spec) => db::MailAccess::insert(pool, check, spec).await,
+      api::Ssh(spec) => db::Ssh::insert(pool, check, spec).await,
+      api::Grpc(spec) => db::Grpc::insert(pool, check, spec).await,
+      api::WebSocket(spec) => db::WebSocket::insert(pool, check, spec).await,
+      api::Database(spec) => db::Database::insert(pool, check, spec).await,
+      api::Ntp(spec) => db::Ntp::insert(pool, check, spec).await,
+      api::DnsConsistency(spec) => db::DnsConsistency::insert(pool, check, spec).await,
+      api::Dnssec(spec) => db::Dnssec::insert(pool, check, spec).await,
+      api::HttpFlow(spec) => db::HttpFlow::insert(pool, check, spec).await,
+      api::SecurityHeaders(spec) => db::SecurityHeaders::insert(pool, check, spec).await,
+      api::Crawl(spec) => db::Crawl::insert(pool, check, spec).await,
+      api::Prometheus(spec) => db::Prometheus::insert(pool, check, spec).await,
+      api::Exec(spec) => db::Exec::insert(pool, check, spec).await,
+      api::Script(spec) => db::Script::insert(pool, check, spec).await,
+      #[cfg(feature = "wasm")]
+      api::Wasm(spec) => db::Wasm::insert(pool, check, spec).await,
       api::PlayStore(spec) => db::PlayStore::insert(pool, check, spec).await,
       api::AppStore(spec) => db::AppStore::insert(pool, check, spec).await,
       api::Whois(spec) => db::Whois::insert(pool, check, spec).await,
       #[cfg(feature = "python")]
       api::Python(spec) => db::Python::insert(pool, check, spec).await,
       api::DeadManSwitch(spec) => db::DeadManSwitch::insert(pool, check, spec).await,
+      api::External(spec) => db::External::insert(pool, check, spec).await,
       api::Unsupported => Err(anyhow!("cannot insert check with unsupported spec")),
     }
   }
@@ -109,13 +260,138 @@ impl Spec {
       api::Tcp(spec) => db::Tcp::update(conn, check, spec).await,
       api::Udp(spec) => db::Udp::update(conn, check, spec).await,
       api::Tls(spec) => db::Tls::update(conn, check, spec).await,
+      api::Smtp(spec) => db::Smtp::update(conn, check, spec).await,
+      api::MailAccess(spec) => db::MailAccess::update(conn, check, spec).await,
+      api::Ssh(spec) => db::Ssh::update(conn, check, spec).await,
+      api::Grpc(spec) => db::Grpc::update(conn, check, spec).await,
+      api::WebSocket(spec) => db::WebSocket::update(conn, check, spec).await,
+      api::Database(spec) => db::Database::update(conn, check, spec).await,
+      api::Ntp(spec) => db::Ntp::update(conn, check, spec).await,
+      api::DnsConsistency(spec) => db::DnsConsistency::update(conn, check, spec).await,
+      api::Dnssec(spec) => db::Dnssec::update(conn, check, spec).await,
+      api::HttpFlow(spec) => db::HttpFlow::update(conn, check, spec).await,
+      api::SecurityHeaders(spec) => db::SecurityHeaders::update(conn, check, spec).await,
+      api::Crawl(spec) => db::Crawl::update(conn, check, spec).await,
+      api::Prometheus(spec) => db::Prometheus::update(conn, check, spec).await,
+      api::Exec(spec) => db::Exec::update(conn, check, spec).await,
+      api::Script(spec) => db::Script::update(conn, check, spec).await,
+      #[cfg(feature = "wasm")]
+      api::Wasm(spec) => db::Wasm::update(conn, check, spec).await,
       api::PlayStore(spec) => db::PlayStore::update(conn, check, spec).await,
       api::AppStore(spec) => db::AppStore::update(conn, check, spec).await,
       api::Whois(spec) => db::Whois::update(conn, check, spec).await,
       #[cfg(feature = "python")]
       api::Python(spec) => db::Python::update(conn, check, spec).await,
       api::DeadManSwitch(spec) => db::DeadManSwitch::update(conn, check, spec).await,
+      api::External(spec) => db::External::update(conn, check, spec).await,
       api::Unsupported => Err(anyhow!("cannot update check with unsupported spec")),
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use anyhow::Result;
+  use serde_json::json;
+
+  use super::Spec;
+  use crate::{config, model::CheckKind};
+
+  #[test]
+  fn deserialize_builtin() -> Result<()> {
+    let spec: Spec = serde_json::from_value(json!({ "kind": "tcp", "host": "example.com", "port": 80 }))?;
+
+    assert!(matches!(spec, Spec::Tcp(ref spec) if spec.host == "example.com"));
+
+    let err = serde_json::from_value::<Spec>(json!({ "kind": "tcp", "host": "example.com" })).unwrap_err();
+    assert_eq!(err.to_string(), "missing field `port`");
+
+    Ok(())
+  }
+
+  #[test]
+  fn deserialize_external() -> Result<()> {
+    config::register_external_handlers(&["disk_usage".to_string()]);
+
+    let value = json!({ "kind": "disk_usage", "mount": "/var", "threshold": 90, "timeout": "10s" });
+    let spec: Spec = serde_json::from_value(value.clone())?;
+
+    assert_eq!(spec.kind(), CheckKind::External);
+    assert!(matches!(spec, Spec::External(ref spec) if spec.handler == "disk_usage" && spec.spec.len() == 2 && spec.timeout.is_some()));
+    assert_eq!(serde_json::to_value(&spec)?, value);
+
+    let err = serde_json::from_value::<Spec>(json!({ "kind": "disk_usge", "mount": "/var" })).unwrap_err();
+    assert!(err.to_string().starts_with("unknown variant `disk_usge`"));
+
+    Ok(())
+  }
+
+  #[test]
+  fn redact_mail_access() -> Result<()> {
+    let spec: Spec = serde_json::from_value(json!({ "kind": "mail_access", "host": "imap.example.com", "port": 993, "username": "monitor", "password": "secret" }))?;
+
+    assert!(matches!(spec.redacted(), Spec::MailAccess(ref spec) if spec.username.as_deref() == Some("monitor") && spec.password.is_none()));
+
+    Ok(())
+  }
+
+  #[test]
+  fn redact_http_flow() -> Result<()> {
+    let spec: Spec = serde_json::from_value(json!({
+      "kind": "http_flow",
+      "steps": [
+        { "url": "https://example.com/login", "username": "monitor", "password": "secret" },
+        { "url": "https://example.com/account", "token": "secret", "client_key": "c2VjcmV0" },
+      ]
+    }))?;
+
+    let Spec::HttpFlow(spec) = spec.redacted() else { panic!("spec is not an HTTP flow") };
+
+    assert_eq!(spec.steps[0].request.username.as_deref(), Some("monitor"));
+    assert!(spec
+      .steps
+      .iter()
+      .all(|step| step.request.password.is_none() && step.request.token.is_none() && step.request.client_key.is_none()));
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_redirects() -> Result<()> {
+    let spec: Spec = serde_json::from_value(json!({ "kind": "http", "url": "http://example.com", "follow_redirects": false, "max_hops": 2 }))?;
+    assert!(spec.validate().is_err());
+
+    let spec: Spec = serde_json::from_value(json!({ "kind": "http_flow", "steps": [{ "url": "http://example.com", "follow_redirects": false, "no_downgrade": true }] }))?;
+    assert!(spec.validate().is_err());
+
+    let spec: Spec = serde_json::from_value(json!({ "kind": "http", "url": "http://example.com", "max_hops": 2 }))?;
+    assert!(spec.validate().is_ok());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_mail_access() -> Result<()> {
+    let spec: Spec = serde_json::from_value(json!({ "kind": "mail_access", "host": "mail.example.com", "port": 993, "min_messages": 1 }))?;
+    assert!(spec.validate().is_err());
+
+    let spec: Spec = serde_json::from_value(json!({ "kind": "mail_access", "host": "mail.example.com", "port": 993, "username": "support", "min_messages": 1 }))?;
+    assert!(spec.validate().is_ok());
+
+    Ok(())
+  }
+
+  #[test]
+  fn validate_security_headers() -> Result<()> {
+    let spec: Spec = serde_json::from_value(json!({ "kind": "security_headers", "url": "https://example.com", "mandatory": ["hsts", "csp"] }))?;
+    assert!(spec.validate().is_ok());
+
+    let spec: Spec = serde_json::from_value(json!({ "kind": "security_headers", "url": "https://example.com", "mandatory": ["hsts", "cookie"] }))?;
+    assert_eq!(
+      spec.validate().map_err(|err| err.to_string()),
+      Err("unknown audit `cookie` in `mandatory`, expected one of hsts, csp, content_type_options, frame_options, cookies, https_redirect".to_string())
+    );
+
+    Ok(())
+  }
+}
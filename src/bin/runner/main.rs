@@ -81,7 +81,12 @@ async fn main() -> Result<()> {
 }
 
 async fn run_check(config: Arc<Config>, stash: Stash, mut inhibitor: Inhibitor, claims: &RunnerClaims, check: api::RunnerCheck) -> Result<()> {
-  let dummy = Check { id: check.id, ..Default::default() };
+  // The UUID scopes values kept in the stash across runs of the check.
+  let dummy = Check {
+    id: check.id,
+    uuid: check.uuid.clone(),
+    ..Default::default()
+  };
 
   let result = match check.spec {
     #[cfg(feature = "ping")]
@@ -100,6 +105,57 @@ async fn run_check(config: Arc<Config>, stash: Stash, mut inhibitor: Inhibitor,
     Spec::Tcp(ref spec) => TcpHandler { check: &dummy }.run(spec, &config.site, stash).await,
     Spec::Udp(ref spec) => UdpHandler { check: &dummy }.run(spec, &config.site, stash).await,
     Spec::Tls(ref spec) => TlsHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Smtp(ref spec) => SmtpHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::MailAccess(ref spec) => MailAccessHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Ssh(ref spec) => SshHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Grpc(ref spec) => GrpcHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::WebSocket(ref spec) => WebSocketHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Database(ref spec) => DatabaseHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Ntp(ref spec) => NtpHandler { check: &dummy }.run(spec, &config.site, stash).await,
+
+    Spec::DnsConsistency(ref spec) => {
+      DnsConsistencyHandler {
+        check: &dummy,
+        resolver: config.checks.dns_resolver,
+      }
+      .run(spec, &config.site, stash)
+      .await
+    }
+
+    Spec::Dnssec(ref spec) => {
+      DnssecHandler {
+        check: &dummy,
+        resolver: config.checks.dns_resolver,
+      }
+      .run(spec, &config.site, stash)
+      .await
+    }
+
+    Spec::HttpFlow(ref spec) => HttpFlowHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::SecurityHeaders(ref spec) => SecurityHeadersHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Crawl(ref spec) => CrawlHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Prometheus(ref spec) => PrometheusHandler { check: &dummy }.run(spec, &config.site, stash).await,
+    Spec::Exec(ref spec) => {
+      ExecHandler {
+        check: &dummy,
+        path: config.checks.plugins_path.clone(),
+      }
+      .run(spec, &config.site, stash)
+      .await
+    }
+
+    Spec::Script(ref spec) => ScriptHandler { check: &dummy }.run(spec, &config.site, stash).await,
+
+    #[cfg(feature = "wasm")]
+    Spec::Wasm(ref spec) => {
+      WasmHandler {
+        check: &dummy,
+        path: config.checks.plugins_path.clone(),
+      }
+      .run(spec, &config.site, stash)
+      .await
+    }
+
     Spec::PlayStore(ref spec) => PlayStoreHandler { check: &dummy }.run(spec, &config.site, stash).await,
     Spec::AppStore(ref spec) => AppStoreHandler { check: &dummy }.run(spec, &config.site, stash).await,
     Spec::Whois(ref spec) => WhoisHandler { check: &dummy }.run(spec, &config.site, stash).await,
@@ -115,6 +171,16 @@ async fn run_check(config: Arc<Config>, stash: Stash, mut inhibitor: Inhibitor,
     }
 
     Spec::DeadManSwitch(_) => Err(anyhow!("deadmanswitch check cannot be run")),
+
+    Spec::External(ref spec) => {
+      ExternalHandler {
+        check: &dummy,
+        path: config.checks.handlers_path.clone(),
+      }
+      .run(spec, &config.site, stash)
+      .await
+    }
+
     Spec::Unsupported => Err(anyhow!("cannot run check")),
   };
 
@@ -1,7 +1,8 @@
 use std::{
   env, fs,
   net::{IpAddr, Ipv4Addr, SocketAddr},
-  sync::Arc,
+  os::unix::fs::PermissionsExt,
+  sync::{Arc, RwLock},
   time::Duration,
 };
 
@@ -9,7 +10,10 @@ use anyhow::{Context, Result};
 use kvlogger::KvLoggerBuilder;
 use once_cell::sync::Lazy;
 
-use crate::{ext::EnvExt, model::CHECK_KINDS};
+use crate::{
+  ext::EnvExt,
+  model::{CheckKind, CHECK_KINDS},
+};
 
 pub const CONTROLLER_ID: &str = "@controller";
 
@@ -20,6 +24,25 @@ pub static PUBLIC_KEY: Lazy<Option<Vec<u8>>> = Lazy::new(|| {
     .ok()
 });
 
+/// Names of the external handlers found while parsing the configuration, so specs can recognize them as check kinds.
+static EXTERNAL_HANDLERS: Lazy<RwLock<Vec<String>>> = Lazy::new(Default::default);
+
+/// Register external handlers, whose names are then deserialized as external check kinds.
+pub fn register_external_handlers(handlers: &[String]) {
+  let mut registered = EXTERNAL_HANDLERS.write().unwrap();
+
+  for handler in handlers {
+    if !registered.contains(handler) {
+      registered.push(handler.clone());
+    }
+  }
+}
+
+/// Whether a check kind names a registered external handler.
+pub fn is_external_handler(kind: &str) -> bool {
+  EXTERNAL_HANDLERS.read().unwrap().iter().any(|handler| handler == kind)
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Config {
   pub domain: String,
@@ -142,6 +165,9 @@ impl DmsConfig {
 #[derive(Debug, Clone, Serialize)]
 pub struct ChecksConfig {
   pub dns_resolver: IpAddr,
+  pub plugins_path: String,
+  pub handlers_path: String,
+  pub handlers: Vec<String>,
   #[cfg(feature = "python")]
   pub scripts_path: String,
 }
@@ -153,17 +179,39 @@ impl ChecksConfig {
       Err(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 53),
     };
 
+    let plugins_path = env::var("PLUGINS_PATH").or_string("/var/lib/defcon/plugins");
+    let handlers_path = env::var("HANDLERS_PATH").or_string("/var/lib/defcon/handlers");
+    let handlers = external_handlers(&handlers_path);
+    register_external_handlers(&handlers);
     #[cfg(feature = "python")]
     let scripts_path = env::var("SCRIPTS_PATH").or_string("/var/lib/defcon/scripts");
 
     Ok(ChecksConfig {
       dns_resolver: resolver.ip(),
+      plugins_path,
+      handlers_path,
+      handlers,
       #[cfg(feature = "python")]
       scripts_path,
     })
   }
 }
 
+/// List the executables in the handlers directory, each registered as a check kind named after its file.
+fn external_handlers(path: &str) -> Vec<String> {
+  let Ok(entries) = fs::read_dir(path) else { return Vec::new() };
+
+  let mut handlers = entries
+    .flatten()
+    .filter(|entry| entry.metadata().is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0))
+    .filter_map(|entry| entry.file_name().into_string().ok())
+    .filter(|name| CheckKind::try_from(name.clone()).is_err())
+    .collect::<Vec<_>>();
+
+  handlers.sort();
+  handlers
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct AlertersConfig {
   pub default: Option<String>,
@@ -202,7 +250,7 @@ impl Config {
   }
 
   pub fn parse() -> Result<Arc<Config>> {
-    let config = Config {
+    let mut config = Config {
       domain: env::var("DOMAIN").unwrap_or_default(),
       api: ApiConfig::new()?,
       #[cfg(feature = "web")]
@@ -216,17 +264,20 @@ impl Config {
       features: FeaturesConfig::default(),
     };
 
+    config.features.handlers.extend(config.checks.handlers.iter().cloned());
+
     Ok(Arc::new(config))
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use std::{env, fs, net::Ipv4Addr, time::Duration};
+  use std::{env, fs, net::Ipv4Addr, os::unix::fs::PermissionsExt, time::Duration};
 
   use anyhow::Result;
   use jsonwebtoken::DecodingKey;
   use serial_test::serial;
+  use uuid::Uuid;
 
   use super::Config;
 
@@ -290,6 +341,7 @@ mod tests {
     env::set_var("CLEANER_ENABLE", "1");
     env::set_var("CLEANER_INTERVAL", "10s");
     env::set_var("CLEANER_THRESHOLD", "10s");
+    env::set_var("PLUGINS_PATH", "/custom/plugins");
     #[cfg(feature = "python")]
     env::set_var("SCRIPTS_PATH", "/custom/path");
 
@@ -310,6 +362,7 @@ mod tests {
     assert_eq!(config.cleaner.enable, true);
     assert_eq!(config.cleaner.interval, Duration::from_secs(10));
     assert_eq!(config.cleaner.threshold, Duration::from_secs(10));
+    assert_eq!(&config.checks.plugins_path, "/custom/plugins");
     #[cfg(feature = "python")]
     assert_eq!(&config.checks.scripts_path, "/custom/path");
 
@@ -326,9 +379,38 @@ mod tests {
     env::remove_var("CLEANER_ENABLE");
     env::remove_var("CLEANER_INTERVAL");
     env::remove_var("CLEANER_THRESHOLD");
+    env::remove_var("PLUGINS_PATH");
     #[cfg(feature = "python")]
     env::remove_var("SCRIPTS_PATH");
 
     Ok(())
   }
+
+  #[test]
+  #[serial]
+  fn external_handlers() -> Result<()> {
+    write_keys()?;
+
+    let path = env::temp_dir().join(format!("defcon-handlers-{}", Uuid::new_v4().simple()));
+    fs::create_dir(&path)?;
+
+    for (name, mode) in [("disk_usage", 0o755), ("queue_lag", 0o755), ("README", 0o644), ("http", 0o755)] {
+      fs::write(path.join(name), "#!/bin/sh\n")?;
+      fs::set_permissions(path.join(name), fs::Permissions::from_mode(mode))?;
+    }
+
+    env::set_var("HANDLERS_PATH", &path);
+
+    let config = Config::parse()?;
+
+    assert_eq!(config.checks.handlers, vec!["disk_usage".to_string(), "queue_lag".to_string()]);
+    assert!(config.features.handlers.contains(&"queue_lag".to_string()));
+    assert!(super::is_external_handler("queue_lag"));
+    assert!(!super::is_external_handler("README"));
+
+    env::remove_var("HANDLERS_PATH");
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
 }
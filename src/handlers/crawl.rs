@@ -0,0 +1,328 @@
+use std::{
+  collections::{HashSet, VecDeque},
+  sync::Arc,
+  time::Instant,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sqlx::MySqlConnection;
+use ureq::Agent;
+use url::Url;
+
+use crate::{
+  config::Config,
+  handlers::{http, Handler},
+  model::{
+    specs::{Crawl, Http},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+/// Maximum number of broken URLs listed in the event message.
+const MAX_REPORTED: usize = 5;
+
+/// Time after which the crawl is abandoned, whatever the number of URLs left to request.
+const DEADLINE: std::time::Duration = std::time::Duration::from_secs(60);
+
+static TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<(a|img|link|script|source)\b([^>]*)>").unwrap());
+static ATTRIBUTE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?is)\b(href|rel|src)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap());
+
+pub struct CrawlHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for CrawlHandler<'_> {
+  type Spec = Crawl;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Crawl::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Crawl, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+    let root = Url::parse(&spec.url).context("invalid URL")?;
+    let agent = http::agent(&request(&root), *timeout)?;
+    let deadline = Instant::now() + DEADLINE;
+
+    // Requests are blocking, so the whole crawl runs outside of the runtime.
+    let task = tokio::task::spawn_blocking({
+      let spec = spec.clone();
+
+      move || crawl(&agent, &spec, root, deadline)
+    });
+
+    let result = match tokio::time::timeout(DEADLINE, task).await {
+      Ok(result) => result?,
+      Err(_) => Err(anyhow!("crawl timed out")),
+    };
+
+    let (status, message) = match result {
+      Ok((count, broken)) if broken.is_empty() => (OK, format!("{count} URLs verified")),
+
+      Ok((count, broken)) => {
+        let mut urls = broken.iter().take(MAX_REPORTED).map(String::as_str).collect::<Vec<_>>().join(", ");
+
+        if broken.len() > MAX_REPORTED {
+          urls.push_str(&format!(" and {} more", broken.len() - MAX_REPORTED));
+        }
+
+        (CRITICAL, format!("{} of {count} URLs are broken: {urls}", broken.len()))
+      }
+
+      Err(err) => (CRITICAL, format!("{err:#}")),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Verify the page and every resource it links to, returning how many URLs were requested and which ones were broken.
+fn crawl(agent: &Agent, spec: &Crawl, root: Url, deadline: Instant) -> Result<(usize, Vec<String>)> {
+  let max_depth = spec.max_depth.unwrap_or(1);
+  let max_urls = spec.max_urls.unwrap_or(100) as usize;
+
+  let mut seen = HashSet::from([root.to_string()]);
+  let mut queue = VecDeque::from([(root.clone(), 0, true)]);
+  let mut broken = Vec::new();
+  let mut count = 0;
+
+  while let Some((url, depth, page)) = queue.pop_front() {
+    if count >= max_urls {
+      break;
+    }
+
+    if Instant::now() >= deadline {
+      bail!("crawl timed out");
+    }
+
+    count += 1;
+
+    let response = match http::request(agent, &request(&url)) {
+      Ok(response) if (200..=299).contains(&response.code) => response,
+      Ok(response) if url == root => bail!("page returned status code {}", response.code),
+      Err(err) if url == root => return Err(err),
+
+      Ok(response) => {
+        broken.push(format!("{url} ({})", response.code));
+        continue;
+      }
+
+      Err(_) => {
+        broken.push(format!("{url} (unreachable)"));
+        continue;
+      }
+    };
+
+    if !page || depth >= max_depth {
+      continue;
+    }
+
+    // Relative links are resolved against the URL the page was finally served from.
+    let base = response.hops.last().and_then(|url| Url::parse(url).ok()).unwrap_or(url);
+
+    for (link, anchor) in links(&response.body) {
+      let Ok(mut link) = base.join(&link) else { continue };
+      link.set_fragment(None);
+
+      if !matches!(link.scheme(), "http" | "https") || (anchor && link.origin() != root.origin()) {
+        continue;
+      }
+
+      if seen.insert(link.to_string()) {
+        queue.push_back((link, depth + 1, anchor));
+      }
+    }
+  }
+
+  Ok((count, broken))
+}
+
+/// Build a request following redirects one by one, to know which URL a page was finally served from.
+fn request(url: &Url) -> Http {
+  Http {
+    url: url.to_string(),
+    max_hops: Some(10),
+    ..Default::default()
+  }
+}
+
+/// Extract the URLs of anchors, images, scripts and stylesheets from an HTML document, flagging anchors.
+fn links(html: &str) -> Vec<(String, bool)> {
+  TAG
+    .captures_iter(html)
+    .filter_map(|tag| {
+      let name = tag[1].to_lowercase();
+      let mut href = None;
+      let mut src = None;
+      let mut rel = String::new();
+
+      for attribute in ATTRIBUTE.captures_iter(&tag[2]) {
+        let value = attribute
+          .get(2)
+          .or_else(|| attribute.get(3))
+          .or_else(|| attribute.get(4))
+          .map(|value| value.as_str().trim().to_string())
+          .unwrap_or_default();
+
+        match attribute[1].to_lowercase().as_str() {
+          "href" => href = Some(value),
+          "src" => src = Some(value),
+          _ => rel = value.to_lowercase(),
+        }
+      }
+
+      let link = match name.as_str() {
+        "a" => href.map(|href| (href, true)),
+        "link" if rel.split_whitespace().any(|rel| matches!(rel, "stylesheet" | "icon" | "preload")) => href.map(|href| (href, false)),
+        "link" => None,
+        _ => src.map(|src| (src, false)),
+      };
+
+      link.filter(|(link, _)| !link.is_empty())
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{net::SocketAddr, time::Instant};
+
+  use anyhow::Result;
+
+  use super::{crawl, links, request, CrawlHandler, Handler};
+  use crate::{
+    config::CONTROLLER_ID,
+    handlers::{http, testing},
+    model::{specs::Crawl, status::*, Check},
+    stash::Stash,
+  };
+
+  const INDEX: &str = r#"
+    <html>
+      <head>
+        <link rel="stylesheet" href="/style.css">
+        <link rel="canonical" href="/canonical">
+        <script src='/app.js'></script>
+      </head>
+      <body>
+        <img src=/missing.png alt="">
+        <a href="/about#team">About</a>
+        <a href="mailto:contact@example.com">Contact</a>
+        <a href="https://example.com/">Elsewhere</a>
+      </body>
+    </html>
+  "#;
+
+  const ABOUT: &str = r#"<a href="/">Home</a> <img src="/team.png">"#;
+
+  async fn server() -> Result<SocketAddr> {
+    testing::serve(|request| match request.line() {
+      "GET / HTTP/1.1" => ("200 OK", INDEX),
+      "GET /about HTTP/1.1" => ("200 OK", ABOUT),
+      "GET /style.css HTTP/1.1" | "GET /app.js HTTP/1.1" | "GET /team.png HTTP/1.1" => ("200 OK", ""),
+      _ => ("404 Not Found", ""),
+    })
+    .await
+  }
+
+  #[test]
+  fn crawl_links() {
+    let links = links(INDEX);
+
+    assert_eq!(
+      links,
+      vec![
+        ("/style.css".to_string(), false),
+        ("/app.js".to_string(), false),
+        ("/missing.png".to_string(), false),
+        ("/about#team".to_string(), true),
+        ("mailto:contact@example.com".to_string(), true),
+        ("https://example.com/".to_string(), true),
+      ]
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_crawl_broken() -> Result<()> {
+    let addr = server().await?;
+
+    let handler = CrawlHandler { check: &Check::default() };
+    let spec = Crawl {
+      url: format!("http://{addr}/"),
+      max_depth: Some(2),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(result.message, format!("1 of 6 URLs are broken: http://{addr}/missing.png (404)"));
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_crawl_limits() -> Result<()> {
+    let addr = server().await?;
+
+    let handler = CrawlHandler { check: &Check::default() };
+    let spec = Crawl {
+      url: format!("http://{addr}/"),
+      max_urls: Some(3),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert_eq!(result.message, "3 URLs verified");
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_crawl_page() -> Result<()> {
+    let addr = server().await?;
+
+    let handler = CrawlHandler { check: &Check::default() };
+    let spec = Crawl {
+      url: format!("http://{addr}/missing"),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(result.message, "page returned status code 404");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_crawl_deadline() -> Result<()> {
+    let root = url::Url::parse("http://127.0.0.1/")?;
+    let agent = http::agent(&request(&root), std::time::Duration::from_secs(5))?;
+
+    let result = tokio::task::spawn_blocking(move || crawl(&agent, &Crawl::default(), root, Instant::now())).await?;
+
+    assert_eq!(result.map_err(|err| err.to_string()), Err("crawl timed out".to_string()));
+
+    Ok(())
+  }
+}
@@ -0,0 +1,272 @@
+use std::{sync::Arc, time::Instant};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{ColumnIndex, Connection, Decode, MySqlConnection, PgConnection, Row, Type};
+
+use crate::{
+  config::Config,
+  handlers::Handler,
+  model::{
+    specs::{Database, DatabaseEngine},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+pub struct DatabaseHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for DatabaseHandler<'_> {
+  type Spec = Database;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Database::for_check(conn, self.check).await.context("no spec found")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Database, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+    let dsn = spec.dsn.as_deref().ok_or_else(|| anyhow!("no DSN provided"))?;
+
+    let start = Instant::now();
+    let result = tokio::time::timeout(*timeout, probe(spec.engine, dsn, spec.query.as_deref())).await;
+    let duration = start.elapsed();
+
+    let (status, message) = match result {
+      Ok(Ok(value)) => match (&spec.expected, value) {
+        (Some(expected), Some(value)) if expected.trim() != value.trim() => (CRITICAL, format!("expected `{expected}`, got `{value}`")),
+        (Some(_), None) => (CRITICAL, "no query was provided to compare the result against".to_string()),
+
+        _ => match spec.max_latency {
+          Some(ref maximum) if maximum.0 < duration => (CRITICAL, format!("query took too long ({}ms)", duration.as_millis())),
+          _ => (OK, String::new()),
+        },
+      },
+
+      Ok(Err(err)) => (CRITICAL, format!("{err:#}")),
+      Err(err) => (CRITICAL, err.to_string()),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+async fn probe(engine: DatabaseEngine, dsn: &str, query: Option<&str>) -> Result<Option<String>> {
+  match engine {
+    DatabaseEngine::Mysql => {
+      let mut conn = MySqlConnection::connect(dsn).await.context("could not connect")?;
+
+      let value = match query {
+        Some(query) => Some(scalar(&sqlx::query(query).fetch_one(&mut conn).await.context("query failed")?)?),
+        None => conn.ping().await.context("ping failed").map(|_| None)?,
+      };
+
+      let _ = conn.close().await;
+
+      Ok(value)
+    }
+
+    DatabaseEngine::Postgres => {
+      let mut conn = PgConnection::connect(dsn).await.context("could not connect")?;
+
+      let value = match query {
+        Some(query) => Some(scalar(&sqlx::query(query).fetch_one(&mut conn).await.context("query failed")?)?),
+        None => conn.ping().await.context("ping failed").map(|_| None)?,
+      };
+
+      let _ = conn.close().await;
+
+      Ok(value)
+    }
+
+    DatabaseEngine::Redis => {
+      let client = redis::Client::open(dsn).context("could not parse DSN")?;
+      let mut conn = client.get_multiplexed_async_connection().await.context("could not connect")?;
+
+      let mut args = query.unwrap_or("PING").split_whitespace();
+      let mut command = redis::cmd(args.next().ok_or_else(|| anyhow!("empty command"))?);
+
+      for arg in args {
+        command.arg(arg);
+      }
+
+      let value: redis::Value = command.query_async(&mut conn).await.context("command failed")?;
+
+      match value {
+        redis::Value::Nil => Ok(Some("nil".to_string())),
+        value => redis::from_redis_value::<String>(&value).map(Some).context("unsupported reply type"),
+      }
+    }
+  }
+}
+
+/// Render the first column of a row as a string, whatever its SQL type.
+fn scalar<R: Row>(row: &R) -> Result<String>
+where
+  usize: ColumnIndex<R>,
+  for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+  for<'r> i64: Decode<'r, R::Database> + Type<R::Database>,
+  for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+  for<'r> f64: Decode<'r, R::Database> + Type<R::Database>,
+  for<'r> bool: Decode<'r, R::Database> + Type<R::Database>,
+{
+  fn render<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "NULL".to_string(), |value| value.to_string())
+  }
+
+  row
+    .try_get::<Option<String>, _>(0)
+    .map(render)
+    .or_else(|_| row.try_get::<Option<i64>, _>(0).map(render))
+    .or_else(|_| row.try_get::<Option<i32>, _>(0).map(render))
+    .or_else(|_| row.try_get::<Option<f64>, _>(0).map(render))
+    .or_else(|_| row.try_get::<Option<bool>, _>(0).map(render))
+    .context("unsupported result type")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{env, net::SocketAddr};
+
+  use anyhow::Result;
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+  };
+
+  use super::{DatabaseHandler, Handler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{
+      specs::{Database, DatabaseEngine},
+      status::*,
+      Check,
+    },
+    stash::Stash,
+  };
+
+  // Minimal RESP server answering every command with the same reply.
+  async fn redis(reply: &'static str) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let (reader, mut writer) = stream.into_split();
+      let mut reader = BufReader::new(reader);
+      let mut line = String::new();
+
+      while reader.read_line(&mut line).await.unwrap_or(0) > 0 {
+        if line.starts_with('*') {
+          let count: usize = line[1..].trim().parse().unwrap();
+
+          for _ in 0..count * 2 {
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+          }
+
+          writer.write_all(reply.as_bytes()).await.unwrap();
+        }
+
+        line.clear();
+      }
+    });
+
+    Ok(addr)
+  }
+
+  fn spec(engine: DatabaseEngine, dsn: String) -> Database {
+    Database {
+      id: 0,
+      check_id: 0,
+      engine,
+      dsn: Some(dsn),
+      query: None,
+      expected: None,
+      max_latency: None,
+      timeout: Some(1.into()),
+    }
+  }
+
+  #[tokio::test]
+  async fn handler_database_redis_ping() -> Result<()> {
+    let addr = redis("+PONG\r\n").await?;
+
+    let handler = DatabaseHandler { check: &Check::default() };
+    let spec = Database {
+      expected: Some("PONG".to_string()),
+      query: Some("PING".to_string()),
+      ..spec(DatabaseEngine::Redis, format!("redis://{addr}/"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_database_redis_unexpected_value() -> Result<()> {
+    let addr = redis("$3\r\nbar\r\n").await?;
+
+    let handler = DatabaseHandler { check: &Check::default() };
+    let spec = Database {
+      query: Some("GET foo".to_string()),
+      expected: Some("baz".to_string()),
+      ..spec(DatabaseEngine::Redis, format!("redis://{addr}/"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "expected `baz`, got `bar`");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_database_redis_unreachable() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    drop(listener);
+
+    let handler = DatabaseHandler { check: &Check::default() };
+    let result = handler.run(&spec(DatabaseEngine::Redis, format!("redis://{addr}/")), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("could not connect"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_database_mysql() -> Result<()> {
+    let handler = DatabaseHandler { check: &Check::default() };
+    let spec = Database {
+      query: Some("SELECT 1 + 1".to_string()),
+      expected: Some("2".to_string()),
+      ..spec(DatabaseEngine::Mysql, env::var("DSN")?)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+}
@@ -1,29 +1,44 @@
 use std::{
-  net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+  io::Read,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs},
   str::FromStr,
   sync::Arc,
+  time::Duration,
 };
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use hickory_client::{
   client::{AsyncClient, ClientHandle},
+  op::{Message, MessageType, OpCode, Query},
+  proto::iocompat::AsyncIoTokioAsStd,
   rr::{
     rdata::{caa::Value as CaaValue, CAA},
-    DNSClass, Name, RData,
+    DNSClass, Name, RData, Record, RecordType,
   },
+  tcp::TcpClientStream,
   udp::UdpClientStream,
 };
 use sqlx::MySqlConnection;
-use tokio::net::UdpSocket;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{TcpStream, UdpSocket},
+  time,
+};
 
 use crate::{
   config::Config,
-  handlers::Handler,
-  model::{specs::Dns, status::*, Check, Event},
+  handlers::{starttls, Handler},
+  model::{
+    specs::{Dns, DnsMatching, DnsTransport},
+    status::*,
+    Check, Event,
+  },
   stash::Stash,
 };
 
+const TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct DnsHandler<'h> {
   pub check: &'h Check,
   pub resolver: IpAddr,
@@ -40,40 +55,43 @@ impl Handler for DnsHandler<'_> {
   }
 
   async fn run(&self, spec: &Dns, site: &str, _stash: Stash) -> Result<Event> {
-    let resolver = SocketAddr::new(self.resolver, 53);
-    let conn = UdpClientStream::<UdpSocket>::new(resolver);
-    let (mut client, task) = AsyncClient::connect(conn).await?;
-
-    tokio::spawn(task);
+    let resolver = spec.resolver.clone().unwrap_or_else(|| self.resolver.to_string());
+    let port = spec.port.unwrap_or(match spec.transport {
+      DnsTransport::Udp | DnsTransport::Tcp => 53,
+      DnsTransport::Dot => 853,
+      DnsTransport::Doh => 443,
+    });
 
     let name = Name::from_str(&spec.domain).context("invalid domain")?;
-    let response = client.query(name, DNSClass::IN, spec.record.clone().into()).await.context("query failed")?;
-    let records = response.answers();
-
-    let found = records.iter().try_fold(false, |acc, record| -> Result<_> {
-      match acc {
-        true => Ok(acc),
-        false => match record.data() {
-          Some(RData::NS(ref ns)) => Ok(ns.0 == Name::from_str(&spec.value)?),
-          Some(RData::MX(ref mx)) => Ok(mx.exchange() == &Name::from_str(&spec.value)?),
-          Some(RData::A(ref ip)) => Ok(ip.0 == spec.value.parse::<Ipv4Addr>()?),
-          Some(RData::AAAA(ref ip)) => Ok(ip.0 == spec.value.parse::<Ipv6Addr>()?),
-          Some(RData::CNAME(ref name)) => Ok(name.0 == Name::from_str(&spec.value)?),
-          Some(RData::SRV(ref srv)) => Ok(srv.target() == &Name::from_str(&spec.value)?),
-
-          Some(RData::CAA(CAA {
-            value: CaaValue::Issuer(Some(ref issuer), _),
-            ..
-          })) => Ok(issuer == &Name::from_str(&spec.value)?),
-
-          _ => Ok(false),
-        },
-      }
-    })?;
+    let records = query(spec.transport, &resolver, port, name, spec.record.clone().into()).await?;
 
-    let (status, message) = match found {
-      true => (OK, String::new()),
-      false => (CRITICAL, format!("{} record for {} did not match {}", spec.record, spec.domain, spec.value)),
+    let expected: Vec<&str> = std::iter::once(spec.value.as_str())
+      .filter(|value| !value.is_empty())
+      .chain(spec.values.iter().map(String::as_str))
+      .collect();
+
+    let mut missing = Vec::with_capacity(expected.len());
+
+    for value in &expected {
+      if !records.iter().try_fold(false, |acc, record| -> Result<_> { Ok(acc || matches(record, value)?) })? {
+        missing.push(*value);
+      }
+    }
+
+    let ttl = records.iter().filter(|record| record.record_type() == spec.record.clone().into()).map(Record::ttl).max();
+
+    let (status, message) = match spec.matching {
+      _ if expected.is_empty() && records.is_empty() => (CRITICAL, format!("no {} record found for {}", spec.record, spec.domain)),
+      DnsMatching::Any if !expected.is_empty() && missing.len() == expected.len() => match expected.len() {
+        1 => (CRITICAL, format!("{} record for {} did not match {}", spec.record, spec.domain, expected[0])),
+        _ => (CRITICAL, format!("{} record for {} did not match any of {}", spec.record, spec.domain, expected.join(", "))),
+      },
+      DnsMatching::All if !missing.is_empty() => (CRITICAL, format!("{} record for {} did not match {}", spec.record, spec.domain, missing.join(", "))),
+
+      _ => match (spec.max_ttl, ttl) {
+        (Some(max), Some(ttl)) if ttl > max => (CRITICAL, format!("{} record for {} has a TTL of {ttl}s, above {max}s", spec.record, spec.domain)),
+        _ => (OK, String::new()),
+      },
     };
 
     let event = Event {
@@ -88,21 +106,336 @@ impl Handler for DnsHandler<'_> {
   }
 }
 
+pub(crate) async fn query(transport: DnsTransport, resolver: &str, port: u16, name: Name, record: RecordType) -> Result<Vec<Record>> {
+  // Nameservers given by name often resolve to IPv6 addresses as well, which are not reachable from every runner.
+  let addrs = (resolver, port).to_socket_addrs().context("could not parse resolver")?.collect::<Vec<_>>();
+  let addr = addrs
+    .iter()
+    .find(|addr| addr.is_ipv4())
+    .or_else(|| addrs.first())
+    .copied()
+    .ok_or_else(|| anyhow!("could not parse resolver"))?;
+
+  match transport {
+    DnsTransport::Udp => {
+      let conn = UdpClientStream::<UdpSocket>::with_timeout(addr, TIMEOUT);
+      let (mut client, task) = AsyncClient::connect(conn).await?;
+
+      tokio::spawn(task);
+
+      Ok(client.query(name, DNSClass::IN, record).await.context("query failed")?.answers().to_vec())
+    }
+
+    DnsTransport::Tcp => {
+      let (stream, sender) = TcpClientStream::<AsyncIoTokioAsStd<TcpStream>>::with_timeout(addr, TIMEOUT);
+      let (mut client, task) = AsyncClient::new(stream, sender, None).await.context("could not connect to resolver")?;
+
+      tokio::spawn(task);
+
+      Ok(client.query(name, DNSClass::IN, record).await.context("query failed")?.answers().to_vec())
+    }
+
+    DnsTransport::Dot => {
+      let request = request(name, record).to_vec()?;
+
+      let response = time::timeout(TIMEOUT, async {
+        let stream = TcpStream::connect(addr).await.context("could not connect to resolver")?;
+        let mut stream = starttls::upgrade(stream, resolver).await?;
+
+        stream.write_u16(request.len() as u16).await?;
+        stream.write_all(&request).await?;
+
+        let mut response = vec![0; stream.read_u16().await? as usize];
+        stream.read_exact(&mut response).await?;
+
+        Ok::<_, anyhow::Error>(response)
+      })
+      .await
+      .context("query failed")?
+      .context("query failed")?;
+
+      Ok(Message::from_vec(&response).context("invalid response")?.take_answers())
+    }
+
+    DnsTransport::Doh => {
+      let request = request(name, record).to_vec()?;
+      let host = match addr.ip() {
+        IpAddr::V6(_) if resolver.parse::<Ipv6Addr>().is_ok() => format!("[{resolver}]"),
+        _ => resolver.to_string(),
+      };
+
+      let response = ureq::AgentBuilder::new()
+        .timeout(TIMEOUT)
+        .build()
+        .post(&format!("https://{host}:{port}/dns-query"))
+        .set("content-type", "application/dns-message")
+        .set("accept", "application/dns-message")
+        .send_bytes(&request)
+        .context("query failed")?;
+
+      let mut body = Vec::new();
+      response.into_reader().read_to_end(&mut body).context("could not read response")?;
+
+      Ok(Message::from_vec(&body).context("invalid response")?.take_answers())
+    }
+  }
+}
+
+fn request(name: Name, record: RecordType) -> Message {
+  let mut message = Message::new();
+
+  message
+    .set_id(rand::random())
+    .set_message_type(MessageType::Query)
+    .set_op_code(OpCode::Query)
+    .set_recursion_desired(true)
+    .add_query(Query::query(name, record));
+
+  message
+}
+
+fn matches(record: &Record, value: &str) -> Result<bool> {
+  let found = match record.data() {
+    Some(RData::NS(ref ns)) => ns.0 == Name::from_str(value)?,
+    Some(RData::MX(ref mx)) => mx.exchange() == &Name::from_str(value)?,
+    Some(RData::A(ref ip)) => ip.0 == value.parse::<Ipv4Addr>()?,
+    Some(RData::AAAA(ref ip)) => ip.0 == value.parse::<Ipv6Addr>()?,
+    Some(RData::CNAME(ref name)) => name.0 == Name::from_str(value)?,
+    Some(RData::SRV(ref srv)) => srv.target() == &Name::from_str(value)?,
+
+    Some(RData::CAA(CAA {
+      value: CaaValue::Issuer(Some(ref issuer), _),
+      ..
+    })) => issuer == &Name::from_str(value)?,
+
+    _ => false,
+  };
+
+  Ok(found)
+}
+
 #[cfg(test)]
 mod tests {
-  use std::net::{IpAddr, Ipv4Addr};
+  use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    str::FromStr,
+  };
+
+  use anyhow::Result;
+  use hickory_client::{
+    op::{Message, MessageType},
+    rr::{rdata::A, Name, RData, Record},
+  };
+  use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, UdpSocket},
+  };
 
   use super::{DnsHandler, Handler};
   use crate::{
     config::CONTROLLER_ID,
     model::{
-      specs::{Dns, DnsRecord},
+      specs::{Dns, DnsMatching, DnsRecord, DnsTransport},
       status::*,
       Check,
     },
     stash::Stash,
   };
 
+  fn answer(request: &[u8], addresses: &[Ipv4Addr], ttl: u32) -> Vec<u8> {
+    let request = Message::from_vec(request).unwrap();
+    let mut response = Message::new();
+
+    response.set_id(request.id()).set_message_type(MessageType::Response).add_queries(request.queries().to_vec());
+
+    for address in addresses {
+      response.add_answer(Record::from_rdata(Name::from_str("example.com.").unwrap(), ttl, RData::A(A(*address))));
+    }
+
+    response.to_vec().unwrap()
+  }
+
+  async fn udp_server(addresses: &'static [Ipv4Addr], ttl: u32) -> Result<SocketAddr> {
+    let server = UdpSocket::bind("127.0.0.1:0").await?;
+    let addr = server.local_addr()?;
+
+    tokio::spawn(async move {
+      let mut buf = [0; 512];
+      let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+
+      server.send_to(&answer(&buf[..len], addresses, ttl), &peer).await.unwrap();
+    });
+
+    Ok(addr)
+  }
+
+  async fn tcp_server(addresses: &'static [Ipv4Addr], ttl: u32) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+
+      let mut request = vec![0; stream.read_u16().await.unwrap() as usize];
+      stream.read_exact(&mut request).await.unwrap();
+
+      let response = answer(&request, addresses, ttl);
+      stream.write_u16(response.len() as u16).await.unwrap();
+      stream.write_all(&response).await.unwrap();
+    });
+
+    Ok(addr)
+  }
+
+  fn local_spec(addr: SocketAddr, transport: DnsTransport) -> Dns {
+    Dns {
+      record: DnsRecord::A,
+      domain: "example.com".to_string(),
+      resolver: Some(addr.ip().to_string()),
+      port: Some(addr.port()),
+      transport,
+      ..Default::default()
+    }
+  }
+
+  const ADDRESSES: &[Ipv4Addr] = &[Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)];
+
+  #[tokio::test]
+  async fn handler_dns_resolver_all() -> Result<()> {
+    let addr = udp_server(ADDRESSES, 300).await?;
+
+    let handler = DnsHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let spec = Dns {
+      values: vec!["10.0.0.1".to_string(), "10.0.0.2".to_string()].into(),
+      matching: DnsMatching::All,
+      ..local_spec(addr, DnsTransport::Udp)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_resolver_all_missing() -> Result<()> {
+    let addr = udp_server(ADDRESSES, 300).await?;
+
+    let handler = DnsHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let spec = Dns {
+      values: vec!["10.0.0.1".to_string(), "10.0.0.3".to_string()].into(),
+      matching: DnsMatching::All,
+      ..local_spec(addr, DnsTransport::Udp)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "A record for example.com did not match 10.0.0.3");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_resolver_any() -> Result<()> {
+    let addr = tcp_server(ADDRESSES, 300).await?;
+
+    let handler = DnsHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let spec = Dns {
+      values: vec!["10.0.0.3".to_string(), "10.0.0.2".to_string()].into(),
+      matching: DnsMatching::Any,
+      ..local_spec(addr, DnsTransport::Tcp)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_resolver_ttl() -> Result<()> {
+    let addr = udp_server(ADDRESSES, 3600).await?;
+
+    let handler = DnsHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let spec = Dns {
+      value: "10.0.0.1".to_string(),
+      max_ttl: Some(300),
+      ..local_spec(addr, DnsTransport::Udp)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "A record for example.com has a TTL of 3600s, above 300s");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_dot_ok() -> Result<()> {
+    let handler = DnsHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let spec = Dns {
+      record: DnsRecord::NS,
+      domain: "example.com".to_string(),
+      value: "a.iana-servers.net".to_string(),
+      resolver: Some("one.one.one.one".to_string()),
+      transport: DnsTransport::Dot,
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_doh_ok() -> Result<()> {
+    let handler = DnsHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let spec = Dns {
+      record: DnsRecord::NS,
+      domain: "example.com".to_string(),
+      value: "a.iana-servers.net".to_string(),
+      resolver: Some("cloudflare-dns.com".to_string()),
+      transport: DnsTransport::Doh,
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn handler_dns_ns_ok() {
     let handler = DnsHandler {
@@ -116,6 +449,7 @@ mod tests {
       record: DnsRecord::NS,
       domain: "example.com".to_string(),
       value: "a.iana-servers.net".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -138,6 +472,7 @@ mod tests {
       record: DnsRecord::MX,
       domain: "github.com".to_string(),
       value: "aspmx.l.google.com".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -160,6 +495,7 @@ mod tests {
       record: DnsRecord::A,
       domain: "example.com".to_string(),
       value: "93.184.215.14".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -182,6 +518,7 @@ mod tests {
       record: DnsRecord::AAAA,
       domain: "example.com".to_string(),
       value: "2606:2800:21f:cb07:6820:80da:af6b:8b2c".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -204,6 +541,7 @@ mod tests {
       record: DnsRecord::CNAME,
       domain: "www.github.com".to_string(),
       value: "github.com".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -226,6 +564,7 @@ mod tests {
       record: DnsRecord::CAA,
       domain: "google.com".to_string(),
       value: "pki.goog".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -247,6 +586,7 @@ mod tests {
       record: DnsRecord::A,
       domain: "example.com".to_string(),
       value: "1.2.3.4".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -269,6 +609,7 @@ mod tests {
       record: DnsRecord::A,
       domain: "example.com".to_string(),
       value: "example.com".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
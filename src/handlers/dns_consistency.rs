@@ -0,0 +1,300 @@
+use std::{
+  collections::BTreeMap,
+  net::{IpAddr, SocketAddr},
+  str::FromStr,
+  sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hickory_client::rr::{Name, RData, RecordType};
+use sqlx::MySqlConnection;
+
+use crate::{
+  config::Config,
+  handlers::{dns, Handler},
+  model::{
+    specs::{DnsConsistency, DnsTransport},
+    status::*,
+    Check, Event,
+  },
+  stash::Stash,
+};
+
+pub struct DnsConsistencyHandler<'h> {
+  pub check: &'h Check,
+  pub resolver: IpAddr,
+}
+
+#[async_trait]
+impl Handler for DnsConsistencyHandler<'_> {
+  type Spec = DnsConsistency;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = DnsConsistency::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &DnsConsistency, site: &str, _stash: Stash) -> Result<Event> {
+    let zone = Name::from_str(&spec.zone).context("invalid zone")?;
+    let domain = Name::from_str(spec.domain.as_deref().unwrap_or(&spec.zone)).context("invalid domain")?;
+
+    let (status, message) = match self.compare(spec, &zone, &domain).await {
+      Ok(result) => result,
+      Err(err) => (CRITICAL, format!("{err:#}")),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+impl DnsConsistencyHandler<'_> {
+  /// Query every nameserver of the zone and describe how their answers diverged, if they did.
+  async fn compare(&self, spec: &DnsConsistency, zone: &Name, domain: &Name) -> Result<(u8, String)> {
+    let nameservers = match spec.nameservers.is_empty() {
+      false => spec.nameservers.iter().map(|nameserver| parse_nameserver(nameserver)).collect(),
+
+      true => {
+        let records = dns::query(DnsTransport::Udp, &self.resolver.to_string(), 53, zone.clone(), RecordType::NS)
+          .await
+          .context("could not retrieve NS records")?;
+
+        records
+          .iter()
+          .filter_map(|record| match record.data() {
+            Some(RData::NS(ref ns)) => Some((ns.0.to_string().trim_end_matches('.').to_string(), 53)),
+            _ => None,
+          })
+          .collect::<Vec<_>>()
+      }
+    };
+
+    if nameservers.is_empty() {
+      return Err(anyhow!("no nameserver found for {}", spec.zone));
+    }
+
+    let mut answers = BTreeMap::new();
+
+    for (nameserver, port) in &nameservers {
+      let answer = answer(nameserver, *port, zone, domain, spec.record.clone().into())
+        .await
+        .unwrap_or_else(|err| format!("query failed: {err:#}"));
+
+      match port {
+        53 => answers.insert(nameserver.clone(), answer),
+        _ => answers.insert(format!("{nameserver}:{port}"), answer),
+      };
+    }
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for answer in answers.values() {
+      *counts.entry(answer).or_default() += 1;
+    }
+
+    let result = match counts.len() {
+      1 => (OK, String::new()),
+
+      _ => {
+        let reference = counts.iter().max_by_key(|(_, count)| **count).map(|(answer, _)| *answer).unwrap_or_default();
+        let diverged = answers
+          .iter()
+          .filter(|(_, answer)| answer.as_str() != reference)
+          .map(|(nameserver, _)| nameserver.as_str())
+          .collect::<Vec<_>>();
+        let details = answers.iter().map(|(nameserver, answer)| format!("{nameserver}: {answer}")).collect::<Vec<_>>();
+
+        (CRITICAL, format!("{} diverged; {}", diverged.join(", "), details.join("; ")))
+      }
+    };
+
+    Ok(result)
+  }
+}
+
+/// Describe what a nameserver returned, so answers can be compared as strings.
+async fn answer(nameserver: &str, port: u16, zone: &Name, domain: &Name, record: RecordType) -> Result<String> {
+  let serial = dns::query(DnsTransport::Udp, nameserver, port, zone.clone(), RecordType::SOA)
+    .await?
+    .iter()
+    .find_map(|record| match record.data() {
+      Some(RData::SOA(ref soa)) => Some(soa.serial().to_string()),
+      _ => None,
+    })
+    .unwrap_or_else(|| "none".to_string());
+
+  let mut values = dns::query(DnsTransport::Udp, nameserver, port, domain.clone(), record)
+    .await?
+    .iter()
+    .filter(|answer| answer.record_type() == record)
+    .filter_map(|answer| answer.data().map(ToString::to_string))
+    .collect::<Vec<_>>();
+
+  values.sort();
+
+  let values = match values.is_empty() {
+    true => "none".to_string(),
+    false => values.join(", "),
+  };
+
+  Ok(format!("serial {serial}, {record} {values}"))
+}
+
+fn parse_nameserver(nameserver: &str) -> (String, u16) {
+  if let Ok(addr) = nameserver.parse::<SocketAddr>() {
+    return (addr.ip().to_string(), addr.port());
+  }
+
+  match nameserver.rsplit_once(':') {
+    Some((host, port)) if !host.contains(':') => match port.parse() {
+      Ok(port) => (host.to_string(), port),
+      Err(_) => (nameserver.to_string(), 53),
+    },
+
+    _ => (nameserver.to_string(), 53),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    str::FromStr,
+  };
+
+  use anyhow::Result;
+  use hickory_client::{
+    op::{Message, MessageType},
+    rr::{
+      rdata::{A, SOA},
+      Name, RData, Record, RecordType,
+    },
+  };
+  use tokio::net::UdpSocket;
+
+  use super::{DnsConsistencyHandler, Handler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{
+      specs::{DnsConsistency, DnsRecord},
+      status::*,
+      Check,
+    },
+    stash::Stash,
+  };
+
+  async fn nameserver(serial: u32, address: Ipv4Addr) -> Result<SocketAddr> {
+    let server = UdpSocket::bind("127.0.0.1:0").await?;
+    let addr = server.local_addr()?;
+
+    tokio::spawn(async move {
+      let mut buf = [0; 512];
+
+      while let Ok((len, peer)) = server.recv_from(&mut buf).await {
+        let request = Message::from_vec(&buf[..len]).unwrap();
+        let name = Name::from_str("example.com.").unwrap();
+
+        let mut response = Message::new();
+        response.set_id(request.id()).set_message_type(MessageType::Response).add_queries(request.queries().to_vec());
+
+        match request.queries()[0].query_type() {
+          RecordType::SOA => response.add_answer(Record::from_rdata(name.clone(), 300, RData::SOA(SOA::new(name.clone(), name.clone(), serial, 3600, 600, 86400, 300)))),
+          _ => response.add_answer(Record::from_rdata(name, 300, RData::A(A(address)))),
+        };
+
+        server.send_to(&response.to_vec().unwrap(), &peer).await.unwrap();
+      }
+    });
+
+    Ok(addr)
+  }
+
+  fn spec(nameservers: &[SocketAddr]) -> DnsConsistency {
+    DnsConsistency {
+      zone: "example.com".to_string(),
+      record: DnsRecord::A,
+      nameservers: nameservers.iter().map(ToString::to_string).collect::<Vec<_>>().into(),
+      ..Default::default()
+    }
+  }
+
+  #[tokio::test]
+  async fn handler_dns_consistency_ok() -> Result<()> {
+    let first = nameserver(2024010101, Ipv4Addr::new(10, 0, 0, 1)).await?;
+    let second = nameserver(2024010101, Ipv4Addr::new(10, 0, 0, 1)).await?;
+
+    let handler = DnsConsistencyHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let result = handler.run(&spec(&[first, second]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_consistency_stale_serial() -> Result<()> {
+    let first = nameserver(2024010102, Ipv4Addr::new(10, 0, 0, 1)).await?;
+    let second = nameserver(2024010102, Ipv4Addr::new(10, 0, 0, 1)).await?;
+    let stale = nameserver(2024010101, Ipv4Addr::new(10, 0, 0, 1)).await?;
+
+    let handler = DnsConsistencyHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let result = handler.run(&spec(&[first, second, stale]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with(&format!("{} diverged; ", stale)));
+    assert!(result.message.contains("serial 2024010101, A 10.0.0.1"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_consistency_different_answer() -> Result<()> {
+    let first = nameserver(2024010101, Ipv4Addr::new(10, 0, 0, 1)).await?;
+    let second = nameserver(2024010101, Ipv4Addr::new(10, 0, 0, 2)).await?;
+
+    let handler = DnsConsistencyHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let result = handler.run(&spec(&[first, second]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.contains("A 10.0.0.2"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_dns_consistency_no_nameservers() -> Result<()> {
+    // Nothing answers DNS queries on the loopback address, so the nameservers cannot be discovered.
+    let handler = DnsConsistencyHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::LOCALHOST),
+    };
+
+    let result = handler.run(&spec(&[]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("could not retrieve NS records"));
+
+    Ok(())
+  }
+}
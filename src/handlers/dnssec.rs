@@ -0,0 +1,183 @@
+use std::{
+  net::{IpAddr, SocketAddr},
+  str::FromStr,
+  sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use hickory_client::{
+  client::{AsyncClient, AsyncDnssecClient, ClientHandle},
+  op::{Edns, Message, MessageType, OpCode, Query},
+  proto::{
+    rr::dnssec::rdata::DNSSECRData,
+    xfer::{DnsHandle, DnsRequest, DnsRequestOptions, FirstAnswer},
+  },
+  rr::{DNSClass, Name, RData, RecordType},
+  udp::UdpClientStream,
+};
+use sqlx::MySqlConnection;
+use tokio::net::UdpSocket;
+
+use crate::{
+  config::Config,
+  handlers::Handler,
+  model::{specs::Dnssec, status::*, Check, Event},
+  stash::Stash,
+};
+
+pub struct DnssecHandler<'h> {
+  pub check: &'h Check,
+  pub resolver: IpAddr,
+}
+
+#[async_trait]
+impl Handler for DnssecHandler<'_> {
+  type Spec = Dnssec;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Dnssec::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Dnssec, site: &str, _stash: Stash) -> Result<Event> {
+    let resolver = SocketAddr::new(self.resolver, 53);
+    let name = Name::from_str(&spec.domain).context("invalid domain")?;
+    let record: RecordType = spec.record.clone().into();
+
+    let (mut client, task) = AsyncDnssecClient::connect(UdpClientStream::<UdpSocket>::new(resolver)).await?;
+
+    tokio::spawn(task);
+
+    let (status, message) = match client.query(name.clone(), DNSClass::IN, record).await {
+      Err(err) => (CRITICAL, format!("DNSSEC validation failed: {err}")),
+
+      Ok(response) if !response.answers().iter().any(|answer| answer.record_type() == record) => (CRITICAL, format!("no validated {} record found for {}", spec.record, spec.domain)),
+
+      Ok(_) => match signatures(resolver, name, record).await.map(|expirations| expirations.into_iter().min()) {
+        Ok(Some(expiration)) => expiry(spec, expiration as i64, Utc::now().timestamp()),
+        Ok(None) => (CRITICAL, format!("no signature found for {} records of {}", spec.record, spec.domain)),
+        Err(err) => (CRITICAL, format!("{err:#}")),
+      },
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Retrieve the expiration timestamps of the RRSIGs covering the requested records.
+async fn signatures(resolver: SocketAddr, name: Name, record: RecordType) -> Result<Vec<u32>> {
+  let (client, task) = AsyncClient::connect(UdpClientStream::<UdpSocket>::new(resolver)).await?;
+
+  tokio::spawn(task);
+
+  let mut edns = Edns::new();
+  edns.set_dnssec_ok(true);
+
+  let mut message = Message::new();
+  message
+    .set_id(rand::random())
+    .set_message_type(MessageType::Query)
+    .set_op_code(OpCode::Query)
+    .set_recursion_desired(true)
+    .add_query(Query::query(name, record))
+    .set_edns(edns);
+
+  let response = client.send(DnsRequest::new(message, DnsRequestOptions::default())).first_answer().await.context("query failed")?;
+
+  let expirations = response
+    .answers()
+    .iter()
+    .filter_map(|answer| match answer.data() {
+      Some(RData::DNSSEC(DNSSECRData::RRSIG(ref rrsig))) if rrsig.type_covered() == record => Some(rrsig.sig_expiration()),
+      _ => None,
+    })
+    .collect();
+
+  Ok(expirations)
+}
+
+fn expiry(spec: &Dnssec, expiration: i64, now: i64) -> (u8, String) {
+  let remaining = expiration - now;
+  let days = remaining / 86400;
+
+  if remaining <= 0 {
+    (CRITICAL, format!("Signature for {} records expired {} days ago", spec.record, -days))
+  } else if remaining as u64 <= spec.window.as_secs() {
+    (WARNING, format!("Signature for {} records expires in {days} days", spec.record))
+  } else {
+    (OK, format!("Signature for {} records expires in {days} days", spec.record))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    convert::TryFrom,
+    net::{IpAddr, Ipv4Addr},
+  };
+
+  use super::{expiry, DnssecHandler, Handler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{
+      specs::{DnsRecord, Dnssec},
+      status::*,
+      Check, Duration,
+    },
+    stash::Stash,
+  };
+
+  fn spec(domain: &str) -> Dnssec {
+    Dnssec {
+      id: 0,
+      check_id: 0,
+      domain: domain.to_string(),
+      record: DnsRecord::A,
+      window: Duration::try_from("7 days").unwrap(),
+    }
+  }
+
+  #[test]
+  fn dnssec_expiry() {
+    let now = 1_700_000_000;
+
+    assert_eq!(expiry(&spec("example.com"), now + 30 * 86400, now), (OK, "Signature for A records expires in 30 days".to_string()));
+    assert_eq!(expiry(&spec("example.com"), now + 3 * 86400, now), (WARNING, "Signature for A records expires in 3 days".to_string()));
+    assert_eq!(expiry(&spec("example.com"), now - 2 * 86400, now), (CRITICAL, "Signature for A records expired 2 days ago".to_string()));
+  }
+
+  #[tokio::test]
+  async fn handler_dnssec_ok() {
+    let handler = DnssecHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let result = handler.run(&spec("cloudflare.com"), CONTROLLER_ID, Stash::new()).await.unwrap();
+
+    assert_ne!(result.status, CRITICAL);
+  }
+
+  #[tokio::test]
+  async fn handler_dnssec_bogus() {
+    let handler = DnssecHandler {
+      check: &Check::default(),
+      resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    };
+
+    let result = handler.run(&spec("dnssec-failed.org"), CONTROLLER_ID, Stash::new()).await.unwrap();
+
+    assert_eq!(result.status, CRITICAL);
+  }
+}
@@ -0,0 +1,208 @@
+use std::{
+  path::{Path, PathBuf},
+  process::Stdio,
+  sync::Arc,
+};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use sqlx::MySqlConnection;
+use tokio::{process::Command, time::timeout};
+
+use crate::{
+  config::Config,
+  handlers::Handler,
+  model::{specs::Exec, status::*, Check, Duration, Event},
+  stash::Stash,
+};
+
+pub struct ExecHandler<'h> {
+  pub check: &'h Check,
+  pub path: String,
+}
+
+#[async_trait]
+impl Handler for ExecHandler<'_> {
+  type Spec = Exec;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Exec::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Exec, site: &str, _stash: Stash) -> Result<Event> {
+    let (status, message) = match self.execute(spec).await {
+      Ok(result) => result,
+      Err(err) => (CRITICAL, format!("{err:#}")),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+impl ExecHandler<'_> {
+  /// Run the plugin and map its exit code and first line of output to a status and message, following the Nagios plugin API.
+  async fn execute(&self, spec: &Exec) -> Result<(u8, String)> {
+    let command = plugin(&self.path, &spec.command)?;
+
+    let child = Command::new(&command)
+      .args(spec.arguments.iter())
+      .stdin(Stdio::null())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .kill_on_drop(true)
+      .spawn()
+      .with_context(|| format!("could not execute {}", spec.command))?;
+
+    let output = timeout(*spec.timeout.unwrap_or_else(|| Duration::from(5)), child.wait_with_output())
+      .await
+      .map_err(|_| anyhow!("{} timed out", spec.command))??;
+
+    // Plugins print their message on the first line, followed by optional performance data after a pipe.
+    let line = [&output.stdout, &output.stderr]
+      .into_iter()
+      .map(|output| {
+        String::from_utf8_lossy(output)
+          .lines()
+          .next()
+          .unwrap_or_default()
+          .split('|')
+          .next()
+          .unwrap_or_default()
+          .trim()
+          .to_string()
+      })
+      .find(|line| !line.is_empty());
+
+    let (status, message) = match output.status.code() {
+      Some(0) => (OK, line),
+      Some(1) => (WARNING, line),
+      Some(2) => (CRITICAL, line),
+      Some(code) => (UNKNOWN, line.or_else(|| Some(format!("{} exited with status {code}", spec.command)))),
+      None => (UNKNOWN, Some(format!("{} was killed by a signal", spec.command))),
+    };
+
+    Ok((status, message.unwrap_or_default()))
+  }
+}
+
+/// Resolve a plugin inside the plugins directory, refusing anything outside of it.
+pub(crate) fn plugin(root: &str, name: &str) -> Result<PathBuf> {
+  let root = Path::new(root).canonicalize().context("could not read plugins directory")?;
+  let path = root.join(name).canonicalize().with_context(|| format!("could not find {name}"))?;
+
+  if !path.starts_with(&root) {
+    return Err(anyhow!("{name} is outside of the plugins directory"));
+  }
+
+  Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+  };
+
+  use anyhow::Result;
+  use uuid::Uuid;
+
+  use super::{ExecHandler, Handler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::Exec, status::*, Check, Duration},
+    stash::Stash,
+  };
+
+  const PLUGIN: &str = r#"#!/bin/sh
+case "$1" in
+  ok) echo "DISK OK - free space: / 3326 MB (56%) | /=2643MB;5948;5958;0;5968" ;;
+  warning) echo "DISK WARNING - free space: / 812 MB (13%)"; echo "more details" ;;
+  critical) echo "DISK CRITICAL - free space: / 10 MB (1%)"; exit 2 ;;
+  unknown) echo "invalid argument" >&2; exit 3 ;;
+  sleep) sleep 5 ;;
+  *) exit 42 ;;
+esac
+
+[ "$1" = "warning" ] && exit 1
+exit 0
+"#;
+
+  fn plugins() -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("defcon-plugins-{}", Uuid::new_v4().simple()));
+    fs::create_dir(&path)?;
+
+    let plugin = path.join("check_disk");
+    fs::write(&plugin, PLUGIN)?;
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755))?;
+
+    Ok(path)
+  }
+
+  async fn run(path: &Path, command: &str, argument: &str) -> Result<(u8, String)> {
+    let handler = ExecHandler {
+      check: &Check::default(),
+      path: path.to_string_lossy().to_string(),
+    };
+
+    let spec = Exec {
+      command: command.to_string(),
+      arguments: vec![argument.to_string()].into(),
+      timeout: Some(Duration::from(1)),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    Ok((result.status, result.message))
+  }
+
+  #[tokio::test]
+  async fn handler_exec_statuses() -> Result<()> {
+    let path = plugins()?;
+
+    assert_eq!(run(&path, "check_disk", "ok").await?, (OK, "DISK OK - free space: / 3326 MB (56%)".to_string()));
+    assert_eq!(run(&path, "check_disk", "warning").await?, (WARNING, "DISK WARNING - free space: / 812 MB (13%)".to_string()));
+    assert_eq!(run(&path, "check_disk", "critical").await?, (CRITICAL, "DISK CRITICAL - free space: / 10 MB (1%)".to_string()));
+    assert_eq!(run(&path, "check_disk", "unknown").await?, (UNKNOWN, "invalid argument".to_string()));
+    assert_eq!(run(&path, "check_disk", "other").await?, (UNKNOWN, "check_disk exited with status 42".to_string()));
+
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_exec_timeout() -> Result<()> {
+    let path = plugins()?;
+
+    assert_eq!(run(&path, "check_disk", "sleep").await?, (CRITICAL, "check_disk timed out".to_string()));
+
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_exec_outside() -> Result<()> {
+    let path = plugins()?;
+
+    assert_eq!(run(&path, "../../bin/sh", "-c").await?, (CRITICAL, "../../bin/sh is outside of the plugins directory".to_string()));
+    assert!(run(&path, "check_procs", "").await?.1.starts_with("could not find check_procs"));
+
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,261 @@
+use std::{collections::HashMap, process::Stdio, sync::Arc};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde_json::json;
+use sqlx::MySqlConnection;
+use tokio::{io::AsyncWriteExt, process::Command, time::timeout};
+
+use crate::{
+  config::Config,
+  handlers::{exec::plugin, Handler},
+  model::{specs::External, status::*, Check, Duration, Event},
+  stash::Stash,
+};
+
+/// Event written back by an external handler on its standard output.
+#[derive(Debug, Deserialize)]
+struct Response {
+  status: u8,
+  #[serde(default)]
+  message: String,
+  #[serde(default)]
+  stash: HashMap<String, Option<String>>,
+}
+
+pub struct ExternalHandler<'h> {
+  pub check: &'h Check,
+  pub path: String,
+}
+
+#[async_trait]
+impl Handler for ExternalHandler<'_> {
+  type Spec = External;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = External::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &External, site: &str, stash: Stash) -> Result<Event> {
+    let (status, message) = match self.execute(spec, site, stash).await {
+      Ok(result) => result,
+      Err(err) => (CRITICAL, format!("{err:#}")),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+impl ExternalHandler<'_> {
+  /// Run the handler with the spec and context as JSON on its standard input, and read back the event it writes out.
+  async fn execute(&self, spec: &External, site: &str, mut stash: Stash) -> Result<(u8, String)> {
+    let command = plugin(&self.path, &spec.handler)?;
+
+    let input = json!({
+      "spec": spec.spec,
+      "site": site,
+      "check": self.check.uuid,
+      "stash": stash.entries(self.check).await,
+    });
+
+    let mut child = Command::new(&command)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .kill_on_drop(true)
+      .spawn()
+      .with_context(|| format!("could not execute {}", spec.handler))?;
+
+    let output = timeout(*spec.timeout.unwrap_or_else(|| Duration::from(5)), async {
+      if let Some(mut stdin) = child.stdin.take() {
+        // Handlers are free to ignore their input, so a closed pipe is not an error.
+        let _ = stdin.write_all(input.to_string().as_bytes()).await;
+      }
+
+      child.wait_with_output().await
+    })
+    .await
+    .map_err(|_| anyhow!("{} timed out", spec.handler))??;
+
+    let response = match serde_json::from_slice::<Response>(&output.stdout) {
+      Ok(response) => response,
+
+      Err(_) if !output.status.success() => {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        return match (output.status.code(), stderr.lines().next().map(str::trim).filter(|line| !line.is_empty())) {
+          (Some(code), Some(line)) => Err(anyhow!("{} exited with status {code}: {line}", spec.handler)),
+          (Some(code), None) => Err(anyhow!("{} exited with status {code}", spec.handler)),
+          (None, _) => Err(anyhow!("{} was killed by a signal", spec.handler)),
+        };
+      }
+
+      Err(err) => return Err(anyhow!("{} returned an invalid event: {err}", spec.handler)),
+    };
+
+    if ![OK, WARNING, CRITICAL, UNKNOWN].contains(&response.status) {
+      return Err(anyhow!("{} returned an invalid status", spec.handler));
+    }
+
+    for (key, value) in response.stash {
+      match value {
+        Some(value) => stash.stash(self.check, &key, &value).await,
+        None => stash.delete(self.check, &key).await,
+      }
+    }
+
+    Ok((response.status, response.message))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+  };
+
+  use anyhow::Result;
+  use serde_json::json;
+  use sqlx::types::Json;
+  use uuid::Uuid;
+
+  use super::{ExternalHandler, Handler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::External, status::*, Check, Duration},
+    stash::Stash,
+  };
+
+  const HANDLER: &str = r#"#!/bin/sh
+input=$(cat)
+
+case "$input" in
+  *'"mode":"ok"'*) echo '{"status": 0, "message": "disk usage is 42%"}' ;;
+  *'"mode":"context"'*) printf '%s' "$input" > "$(dirname "$0")/input.json"; echo '{"status": 1}' ;;
+  *'"runs":"1"'*) echo '{"status": 2, "message": "second run", "stash": {"runs": "2", "first": null}}' ;;
+  *'"mode":"stash"'*) echo '{"status": 0, "message": "first run", "stash": {"runs": "1", "first": "yes"}}' ;;
+  *'"mode":"invalid"'*) echo 'lorem ipsum' ;;
+  *'"mode":"status"'*) echo '{"status": 42}' ;;
+  *'"mode":"fail"'*) echo "disk not mounted" >&2; exit 3 ;;
+  *'"mode":"sleep"'*) sleep 5 ;;
+esac
+"#;
+
+  fn handlers() -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("defcon-handlers-{}", Uuid::new_v4().simple()));
+    fs::create_dir(&path)?;
+
+    let handler = path.join("disk_usage");
+    fs::write(&handler, HANDLER)?;
+    fs::set_permissions(&handler, fs::Permissions::from_mode(0o755))?;
+
+    Ok(path)
+  }
+
+  async fn run(path: &Path, check: &Check, mode: &str, stash: Stash) -> Result<(u8, String)> {
+    let handler = ExternalHandler {
+      check,
+      path: path.to_string_lossy().to_string(),
+    };
+
+    let spec = External {
+      handler: "disk_usage".to_string(),
+      spec: Json(json!({ "mode": mode }).as_object().cloned().unwrap_or_default()),
+      timeout: Some(Duration::from(1)),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, stash).await?;
+
+    Ok((result.status, result.message))
+  }
+
+  #[tokio::test]
+  async fn handler_external_ok() -> Result<()> {
+    let path = handlers()?;
+
+    assert_eq!(run(&path, &Check::default(), "ok", Stash::new()).await?, (OK, "disk usage is 42%".to_string()));
+
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_external_context() -> Result<()> {
+    let path = handlers()?;
+    let check = Check {
+      uuid: "b5fbd5a8-4c3a-4c08-9e5b-bd5f8d2b1a11".to_string(),
+      ..Default::default()
+    };
+
+    let mut stash = Stash::new();
+    stash.stash(&check, "last", "12").await;
+
+    assert_eq!(run(&path, &check, "context", stash).await?, (CRITICAL, String::new()));
+
+    let input = serde_json::from_str::<serde_json::Value>(&fs::read_to_string(path.join("input.json"))?)?;
+
+    assert_eq!(
+      input,
+      json!({
+        "spec": { "mode": "context" },
+        "site": CONTROLLER_ID,
+        "check": "b5fbd5a8-4c3a-4c08-9e5b-bd5f8d2b1a11",
+        "stash": { "last": "12" },
+      })
+    );
+
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_external_stash() -> Result<()> {
+    let path = handlers()?;
+    let check = Check::default();
+    let stash = Stash::new();
+
+    assert_eq!(run(&path, &check, "stash", stash.clone()).await?, (OK, "first run".to_string()));
+    assert_eq!(stash.retrieve(&check, "first").await, Some("yes".to_string()));
+
+    assert_eq!(run(&path, &check, "stash", stash.clone()).await?, (WARNING, "second run".to_string()));
+    assert_eq!(stash.retrieve(&check, "runs").await, Some("2".to_string()));
+    assert_eq!(stash.retrieve(&check, "first").await, None);
+
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_external_errors() -> Result<()> {
+    let path = handlers()?;
+    let check = Check::default();
+
+    assert!(run(&path, &check, "invalid", Stash::new()).await?.1.starts_with("disk_usage returned an invalid event"));
+    assert_eq!(run(&path, &check, "status", Stash::new()).await?, (CRITICAL, "disk_usage returned an invalid status".to_string()));
+    assert_eq!(
+      run(&path, &check, "fail", Stash::new()).await?,
+      (CRITICAL, "disk_usage exited with status 3: disk not mounted".to_string())
+    );
+    assert_eq!(run(&path, &check, "sleep", Stash::new()).await?, (CRITICAL, "disk_usage timed out".to_string()));
+
+    fs::remove_dir_all(path)?;
+
+    Ok(())
+  }
+}
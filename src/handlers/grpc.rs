@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::MySqlConnection;
+use tonic::transport::{ClientTlsConfig, Endpoint};
+use tonic_health::pb::{health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest};
+
+use crate::{
+  config::Config,
+  handlers::Handler,
+  model::{specs::Grpc, status::*, Check, Duration, Event},
+  stash::Stash,
+};
+
+pub struct GrpcHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for GrpcHandler<'_> {
+  type Spec = Grpc;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Grpc::for_check(conn, self.check).await.context("no spec found")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Grpc, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+    let scheme = if spec.tls { "https" } else { "http" };
+
+    let mut endpoint = Endpoint::from_shared(format!("{scheme}://{}", spec.target))
+      .context("could not parse target")?
+      .connect_timeout(*timeout)
+      .timeout(*timeout);
+
+    if spec.tls {
+      endpoint = endpoint.tls_config(ClientTlsConfig::new().with_native_roots()).context("could not configure TLS")?;
+    }
+
+    let (status, message) = match endpoint.connect().await {
+      Ok(channel) => {
+        let request = HealthCheckRequest {
+          service: spec.service.clone().unwrap_or_default(),
+        };
+
+        match HealthClient::new(channel).check(request).await {
+          Ok(response) => match response.into_inner().status() {
+            ServingStatus::Serving => (OK, String::new()),
+            ServingStatus::NotServing => (CRITICAL, "service is not serving".to_string()),
+            ServingStatus::ServiceUnknown => (CRITICAL, "service is unknown to the server".to_string()),
+            ServingStatus::Unknown => (WARNING, "service status is unknown".to_string()),
+          },
+
+          Err(status) => (CRITICAL, format!("health check failed: {}", status.message())),
+        }
+      }
+
+      Err(err) => (CRITICAL, format!("{:#}", anyhow!(err).context("could not connect"))),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::SocketAddr;
+
+  use anyhow::Result;
+  use tokio::net::TcpListener;
+  use tokio_stream::wrappers::TcpListenerStream;
+  use tonic::transport::Server;
+  use tonic_health::ServingStatus;
+
+  use super::{GrpcHandler, Handler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::Grpc, status::*, Check},
+    stash::Stash,
+  };
+
+  async fn server(status: ServingStatus) -> Result<SocketAddr> {
+    let (reporter, service) = tonic_health::server::health_reporter();
+    reporter.set_service_status("defcon.Test", status).await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(Server::builder().add_service(service).serve_with_incoming(TcpListenerStream::new(listener)));
+
+    Ok(addr)
+  }
+
+  fn spec(addr: SocketAddr, service: &str) -> Grpc {
+    Grpc {
+      id: 0,
+      check_id: 0,
+      target: addr.to_string(),
+      service: Some(service.to_string()),
+      tls: false,
+      timeout: Some(1.into()),
+    }
+  }
+
+  #[tokio::test]
+  async fn handler_grpc_serving() -> Result<()> {
+    let addr = server(ServingStatus::Serving).await?;
+
+    let handler = GrpcHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, "defcon.Test"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_grpc_not_serving() -> Result<()> {
+    let addr = server(ServingStatus::NotServing).await?;
+
+    let handler = GrpcHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, "defcon.Test"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "service is not serving");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_grpc_unknown() -> Result<()> {
+    let addr = server(ServingStatus::Unknown).await?;
+
+    let handler = GrpcHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, "defcon.Test"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, WARNING);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_grpc_unregistered_service() -> Result<()> {
+    let addr = server(ServingStatus::Serving).await?;
+
+    let handler = GrpcHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, "defcon.Missing"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("health check failed"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_grpc_unreachable() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    drop(listener);
+
+    let handler = GrpcHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, "defcon.Test"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("could not connect"));
+
+    Ok(())
+  }
+}
@@ -1,14 +1,24 @@
-use std::{sync::Arc, time::Instant};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as b64, Engine as _};
+use native_tls::{Identity, TlsConnector};
+use openssl::pkey::PKey;
+use regex::Regex;
 use sha2::{Digest, Sha512};
 use sqlx::MySqlConnection;
+use ureq::{Agent, AgentBuilder, Proxy};
+use url::Url;
 
 use crate::{
   config::Config,
   handlers::Handler,
-  model::{specs::Http, status::*, Check, Duration, Event},
+  model::{
+    specs::{Http, HttpMethod},
+    status::*,
+    Check, Duration, Event,
+  },
   stash::Stash,
 };
 
@@ -28,114 +38,863 @@ impl Handler for HttpHandler<'_> {
 
   async fn run(&self, spec: &Http, site: &str, _stash: Stash) -> Result<Event> {
     let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
-    let mut request = ureq::AgentBuilder::new().timeout(*timeout).build().get(&spec.url).set("user-agent", "defcon");
 
-    for (header, value) in spec.headers.iter() {
-      request = request.set(header, value);
-    }
+    let (status, message) = match request(&agent(spec, *timeout)?, spec) {
+      Ok(response) => {
+        let failures = assertions(spec, &response);
+        let chain = response.hops.join(" -> ");
 
-    let start = Instant::now();
-    let response = request.call();
-    let duration = start.elapsed();
+        match (failures.is_empty(), chain.is_empty()) {
+          (true, _) => (OK, chain),
+          (false, true) => (CRITICAL, failures.join("; ")),
+          (false, false) => (CRITICAL, format!("{} ({chain})", failures.join("; "))),
+        }
+      }
 
-    let response = match response {
-      Ok(response) => Ok(response),
-      Err(ureq::Error::Status(_, response)) => Ok(response),
-      Err(err) => Err(err),
+      Err(err) => (CRITICAL, err.to_string()),
     };
 
-    let event = match response {
-      Ok(response) => {
-        let code = response.status();
-        let body = response.into_string().unwrap_or_default();
-
-        let code_ok = code == spec.code.unwrap_or(code);
-        let content_ok = match spec.content {
-          Some(ref content) => body.contains(content),
-          None => true,
-        };
-
-        let json_ok = {
-          match spec.json_query {
-            #[allow(unused_variables)]
-            Some(ref query) => {
-              #[cfg(not(feature = "jq"))]
-              {
-                log::warn!("http handler `json_query` is used but Defcon was compiled without `jq` feature");
-                true
-              }
-
-              #[cfg(feature = "jq")]
-              jq_rs::run(query, &body).map_or_else(|_| false, |result| result.trim() == "true")
-            }
-
-            None => true,
-          }
-        };
-
-        let digest_ok = match spec.digest {
-          Some(ref digest) => {
-            let mut hasher = Sha512::new();
-            hasher.update(body);
-            let result = hasher.finalize();
-
-            digest == &format!("{result:x}")
-          }
-
-          None => true,
-        };
-
-        let duration_ok = match spec.duration {
-          Some(ref maximum) => maximum.0 > duration,
-          None => true,
-        };
-
-        let (status, message) = match (code_ok, content_ok, digest_ok, json_ok, duration_ok) {
-          (false, _, _, _, _) => (CRITICAL, format!("status code was {code}")),
-          (_, false, _, _, _) => (CRITICAL, "content mismatch".to_string()),
-          (_, _, false, _, _) => (CRITICAL, "digest mismatch".to_string()),
-          (_, _, _, false, _) => (CRITICAL, "JSON query failed".to_string()),
-          (_, _, _, _, false) => (CRITICAL, "request took too long".to_string()),
-          (true, true, true, true, true) => (OK, String::new()),
-        };
-
-        Event {
-          check_id: self.check.id,
-          site: site.to_string(),
-          status,
-          message,
-          ..Default::default()
-        }
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Maximum number of redirects followed when recording the redirect chain.
+const MAX_HOPS: usize = 10;
+
+pub(crate) struct Response {
+  pub code: u16,
+  pub headers: HashMap<String, String>,
+  pub body: String,
+  pub duration: std::time::Duration,
+  pub cookies: Vec<String>,
+  /// URLs requested when following redirects one by one, starting with the one from the spec.
+  pub hops: Vec<String>,
+}
+
+/// Perform the request described by the spec, error status codes being returned as regular responses.
+pub(crate) fn request(agent: &Agent, spec: &Http) -> Result<Response> {
+  if !spec.tracks_redirects() {
+    return send(agent, spec);
+  }
+
+  let mut request = spec.clone();
+  let mut hops = vec![spec.url.clone()];
+  let mut duration = std::time::Duration::ZERO;
+
+  loop {
+    let mut response = send(agent, &request)?;
+    duration += response.duration;
+
+    let location = match (response.code, response.headers.get("location")) {
+      (300..=399, Some(location)) if hops.len() <= MAX_HOPS => location,
+
+      _ => {
+        response.duration = duration;
+        response.hops = hops;
+
+        return Ok(response);
       }
+    };
 
-      Err(err) => Event {
-        check_id: self.check.id,
-        site: site.to_string(),
-        status: CRITICAL,
-        message: err.to_string(),
-        ..Default::default()
-      },
+    let previous = Url::parse(&request.url)?;
+    let next = previous.join(location).context("invalid redirect location")?;
+
+    // Like ureq's own redirect handling, credentials are never sent to another origin.
+    if next.origin() != previous.origin() {
+      request.username = None;
+      request.password = None;
+      request.token = None;
+      request.headers.0.retain(|header, _| !header.eq_ignore_ascii_case("authorization"));
+    }
+
+    request.url = next.to_string();
+
+    if response.code == 303 {
+      request.method = HttpMethod::Get;
+      request.body = None;
+    }
+
+    hops.push(request.url.clone());
+  }
+}
+
+fn send(agent: &Agent, spec: &Http) -> Result<Response> {
+  let mut request = agent.request(&spec.method.to_string(), &spec.url).set("user-agent", "defcon");
+
+  if let Some(ref username) = spec.username {
+    let credentials = b64.encode(format!("{username}:{}", spec.password.as_deref().unwrap_or_default()));
+    request = request.set("authorization", &format!("Basic {credentials}"));
+  }
+
+  if let Some(ref token) = spec.token {
+    request = request.set("authorization", &format!("Bearer {token}"));
+  }
+
+  for (header, value) in spec.headers.iter() {
+    request = request.set(header, value);
+  }
+
+  let start = Instant::now();
+  let response = match spec.body {
+    Some(ref body) => {
+      if request.header("content-type").is_none() && serde_json::from_str::<serde_json::Value>(body).is_ok() {
+        request = request.set("content-type", "application/json");
+      }
+
+      request.send_string(body)
+    }
+
+    None => request.call(),
+  };
+  let duration = start.elapsed();
+
+  let response = match response {
+    Ok(response) => response,
+    Err(ureq::Error::Status(_, response)) => response,
+    Err(err) => return Err(err.into()),
+  };
+
+  let code = response.status();
+  let headers = response
+    .headers_names()
+    .into_iter()
+    .filter_map(|header| response.header(&header).map(|value| (header.to_lowercase(), value.to_owned())))
+    .collect();
+  let cookies = response.all("set-cookie").into_iter().map(ToOwned::to_owned).collect();
+  let body = response.into_string().unwrap_or_default();
+
+  Ok(Response {
+    code,
+    headers,
+    body,
+    duration,
+    cookies,
+    hops: Vec::new(),
+  })
+}
+
+/// Run every assertion from the spec against the response, and return the reasons for which it failed.
+pub(crate) fn assertions(spec: &Http, response: &Response) -> Vec<String> {
+  let Response {
+    code,
+    ref headers,
+    ref body,
+    duration,
+    ref hops,
+    ..
+  } = *response;
+  let mut failures = Vec::new();
+
+  if (spec.code.is_some() || !spec.codes.is_empty()) && spec.code != Some(code) && !spec.codes.iter().any(|codes| accepts(codes, code)) {
+    failures.push(format!("status code was {code}"));
+  }
+
+  for (header, expected) in spec.response_headers.iter() {
+    match headers.get(&header.to_lowercase()) {
+      Some(value) if value.contains(expected.as_str()) => {}
+      Some(value) => failures.push(format!("header `{header}` was `{value}`")),
+      None => failures.push(format!("header `{header}` is missing")),
+    }
+  }
+
+  if spec.tracks_redirects() {
+    failures.extend(redirects(spec, hops));
+  }
+
+  if let Some(ref content) = spec.content {
+    if !body.contains(content) {
+      failures.push("content mismatch".to_string());
+    }
+  }
+
+  if let Some(ref regex) = spec.regex {
+    match Regex::new(regex) {
+      Ok(regex) if regex.is_match(body) => {}
+      Ok(_) => failures.push(format!("content does not match `{regex}`")),
+      Err(_) => failures.push(format!("invalid regex `{regex}`")),
+    }
+  }
+
+  if let Some(ref content) = spec.not_content {
+    if body.contains(content) {
+      failures.push(format!("content contains `{content}`"));
+    }
+  }
+
+  if let Some(ref regex) = spec.not_regex {
+    match Regex::new(regex) {
+      Ok(regex) if !regex.is_match(body) => {}
+      Ok(_) => failures.push(format!("content matches `{regex}`")),
+      Err(_) => failures.push(format!("invalid regex `{regex}`")),
+    }
+  }
+
+  if let Some(ref digest) = spec.digest {
+    let mut hasher = Sha512::new();
+    hasher.update(body);
+    let result = hasher.finalize();
+
+    if digest != &format!("{result:x}") {
+      failures.push("digest mismatch".to_string());
+    }
+  }
+
+  #[allow(unused_variables)]
+  if let Some(ref query) = spec.json_query {
+    #[cfg(not(feature = "jq"))]
+    log::warn!("http handler `json_query` is used but Defcon was compiled without `jq` feature");
+
+    #[cfg(feature = "jq")]
+    if !jq_rs::run(query, body).map_or_else(|_| false, |result| result.trim() == "true") {
+      failures.push("JSON query failed".to_string());
+    }
+  }
+
+  if let Some(ref schema) = spec.json_schema {
+    if let Err(failure) = validate_schema(schema, body) {
+      failures.push(failure);
+    }
+  }
+
+  if let Some(ref maximum) = spec.duration {
+    if maximum.0 <= duration {
+      failures.push("request took too long".to_string());
+    }
+  }
+
+  failures
+}
+
+fn redirects(spec: &Http, hops: &[String]) -> Vec<String> {
+  let mut failures = Vec::new();
+  let last = hops.last().map(String::as_str).unwrap_or(&spec.url);
+
+  if let Some(maximum) = spec.max_hops {
+    let count = hops.len().saturating_sub(1);
+
+    if count > maximum as usize {
+      failures.push(format!("redirected {count} times, expected at most {maximum}"));
+    }
+  }
+
+  if let Some(ref expected) = spec.final_url {
+    let matches = match (Url::parse(last), Url::parse(expected)) {
+      (Ok(last), Ok(expected)) => last == expected,
+      _ => last == expected,
     };
 
-    Ok(event)
+    if !matches {
+      failures.push(format!("final URL was {last}, expected {expected}"));
+    }
+  }
+
+  if let Some(ref expected) = spec.final_host {
+    let host = Url::parse(last).ok().and_then(|url| url.host_str().map(ToOwned::to_owned)).unwrap_or_default();
+
+    if !host.eq_ignore_ascii_case(expected.trim_end_matches('.')) {
+      failures.push(format!("final host was {host}, expected {expected}"));
+    }
+  }
+
+  if spec.no_downgrade {
+    for hop in hops.windows(2) {
+      if hop[0].starts_with("https://") && hop[1].starts_with("http://") {
+        failures.push(format!("redirect from {} to {} downgrades to http", hop[0], hop[1]));
+      }
+    }
+  }
+
+  failures
+}
+
+/// Whether a status code is accepted by an entry of `codes`, which can be a code (`204`), a class (`2xx`) or a range (`200-299`).
+fn accepts(codes: &str, code: u16) -> bool {
+  let codes = codes.trim().to_lowercase();
+
+  match codes.split_once('-') {
+    Some((low, high)) => match (low.trim().parse::<u16>(), high.trim().parse::<u16>()) {
+      (Ok(low), Ok(high)) => (low..=high).contains(&code),
+      _ => false,
+    },
+
+    None => match codes.strip_suffix("xx") {
+      Some(class) => class.parse::<u16>() == Ok(code / 100),
+      None => codes.parse::<u16>() == Ok(code),
+    },
+  }
+}
+
+fn validate_schema(schema: &serde_json::Value, body: &str) -> Result<(), String> {
+  let validator = jsonschema::validator_for(schema).map_err(|err| format!("invalid JSON schema: {err}"))?;
+  let document = serde_json::from_str::<serde_json::Value>(body).map_err(|_| "content is not valid JSON".to_string())?;
+
+  let errors = validator
+    .iter_errors(&document)
+    .map(|err| match err.instance_path.as_str() {
+      "" => err.to_string(),
+      path => format!("{err} at {path}"),
+    })
+    .collect::<Vec<_>>();
+
+  match errors.is_empty() {
+    true => Ok(()),
+    false => Err(format!("JSON schema validation failed: {}", errors.join(", "))),
+  }
+}
+
+pub(crate) fn agent(spec: &Http, timeout: std::time::Duration) -> Result<Agent> {
+  Ok(builder(spec, timeout)?.build())
+}
+
+/// Configure an agent for the spec, which callers can customize further before building it.
+pub(crate) fn builder(spec: &Http, timeout: std::time::Duration) -> Result<AgentBuilder> {
+  let mut builder = AgentBuilder::new().timeout(timeout);
+
+  if !spec.follow_redirects || spec.tracks_redirects() {
+    builder = builder.redirects(0);
+  }
+
+  if let Some(ref proxy) = spec.proxy {
+    builder = builder.proxy(Proxy::new(proxy).context("invalid proxy")?);
+  }
+
+  if spec.client_cert.is_some() || !spec.verify_tls {
+    let mut tls = TlsConnector::builder();
+    tls.danger_accept_invalid_certs(!spec.verify_tls).danger_accept_invalid_hostnames(!spec.verify_tls);
+
+    if let Some(ref certificate) = spec.client_cert {
+      let key = spec.client_key.as_deref().ok_or_else(|| anyhow!("`client_key` is required with `client_cert`"))?;
+
+      // native-tls only reads PKCS#8 keys, so convert whichever PEM format the key was provided in.
+      let key = PKey::private_key_from_pem(key.as_bytes()).context("invalid client key")?.private_key_to_pem_pkcs8()?;
+
+      tls.identity(Identity::from_pkcs8(certificate.as_bytes(), &key).context("invalid client certificate")?);
+    }
+
+    builder = builder.tls_connector(Arc::new(tls.build()?));
   }
+
+  Ok(builder)
 }
 
 #[cfg(test)]
 mod tests {
-  use std::collections::HashMap;
+  use std::{collections::HashMap, net::SocketAddr, pin::Pin};
+
+  use anyhow::Result;
+  use openssl::{
+    asn1::Asn1Time,
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    rsa::Rsa,
+    ssl::{Ssl, SslAcceptor, SslMethod, SslVerifyMode},
+    x509::{X509NameBuilder, X509},
+  };
+  use sqlx::types::Json;
+  use tokio::net::TcpListener;
+  use tokio_openssl::SslStream;
 
   use super::{Handler, HttpHandler};
   use crate::{
     config::CONTROLLER_ID,
+    handlers::testing,
     model::{
-      specs::{Http, HttpHeaders},
+      specs::{Http, HttpHeaders, HttpMethod},
       status::*,
       Check, Duration,
     },
     stash::Stash,
   };
 
+  fn spec(url: &str) -> Http {
+    Http {
+      url: url.to_string(),
+      ..Default::default()
+    }
+  }
+
+  /// Answer every request with `head` (status line and headers), echoing the request back.
+  async fn server(head: &str) -> Result<SocketAddr> {
+    let head = head.to_string();
+
+    testing::serve(move |request| (head.clone(), request.echo())).await
+  }
+
+  fn certificate(name: &str) -> Result<(X509, PKey<Private>)> {
+    let key = PKey::from_rsa(Rsa::generate(2048)?)?;
+
+    let mut subject = X509NameBuilder::new()?;
+    subject.append_entry_by_text("CN", name)?;
+    let subject = subject.build();
+
+    let mut builder = X509::builder()?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(30)?;
+
+    builder.set_version(2)?;
+    builder.set_subject_name(&subject)?;
+    builder.set_issuer_name(&subject)?;
+    builder.set_pubkey(&key)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+    builder.sign(&key, MessageDigest::sha256())?;
+
+    Ok((builder.build(), key))
+  }
+
+  /// Start a TLS server with a self-signed certificate, which requires a client certificate when `mutual` is set.
+  async fn tls_server(mutual: bool, head: &str) -> Result<SocketAddr> {
+    let head = head.to_string();
+    let (certificate, key) = certificate("localhost")?;
+
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+    acceptor.set_certificate(&certificate)?;
+    acceptor.set_private_key(&key)?;
+
+    if mutual {
+      acceptor.set_verify_callback(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT, |_, _| true);
+    }
+
+    let acceptor = acceptor.build();
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let mut stream = SslStream::new(Ssl::new(acceptor.context()).unwrap(), stream).unwrap();
+
+      if Pin::new(&mut stream).accept().await.is_ok() {
+        let _ = testing::respond(stream, |request| (head, request.echo())).await;
+      }
+    });
+
+    Ok(addr)
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_post() -> Result<()> {
+    let addr = server("200 OK").await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      method: HttpMethod::Post,
+      body: Some(r#"{"lorem":"ipsum"}"#.to_string()),
+      content: Some("POST /health HTTP/1.1".to_string()),
+      ..spec(&format!("http://{addr}/health"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!(result.status, OK);
+
+    let spec = Http {
+      content: Some("content-type: application/json\r\n".to_string()),
+      ..spec
+    };
+
+    let result = handler
+      .run(
+        &Http {
+          url: format!("http://{}/health", server("200 OK").await?),
+          ..spec.clone()
+        },
+        CONTROLLER_ID,
+        Stash::new(),
+      )
+      .await?;
+    assert_eq!(result.status, OK);
+
+    let spec = Http {
+      content: Some("\r\n\r\n{\"lorem\":\"ipsum\"}".to_string()),
+      ..spec
+    };
+
+    let result = handler
+      .run(
+        &Http {
+          url: format!("http://{}/health", server("200 OK").await?),
+          ..spec
+        },
+        CONTROLLER_ID,
+        Stash::new(),
+      )
+      .await?;
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_basic_auth() -> Result<()> {
+    let addr = server("200 OK").await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      username: Some("lorem".to_string()),
+      password: Some("ipsum".to_string()),
+      content: Some("authorization: Basic bG9yZW06aXBzdW0=\r\n".to_string()),
+      ..spec(&format!("http://{addr}/"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_bearer_auth() -> Result<()> {
+    let addr = server("200 OK").await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      token: Some("secret".to_string()),
+      content: Some("authorization: Bearer secret\r\n".to_string()),
+      ..spec(&format!("http://{addr}/"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_no_redirects() -> Result<()> {
+    let addr = server("302 Found\r\nlocation: /elsewhere").await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      follow_redirects: false,
+      code: Some(302),
+      ..spec(&format!("http://{addr}/"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_proxy() -> Result<()> {
+    let addr = server("200 OK").await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      proxy: Some(format!("http://{addr}")),
+      content: Some("GET http://example.invalid/ HTTP/1.1".to_string()),
+      ..spec("http://example.invalid/")
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_verify_tls() -> Result<()> {
+    let handler = HttpHandler { check: &Check::default() };
+
+    let addr = tls_server(false, "200 OK").await?;
+    let result = handler.run(&spec(&format!("https://localhost:{}/", addr.port())), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+
+    let addr = tls_server(false, "200 OK").await?;
+    let spec = Http {
+      verify_tls: false,
+      ..spec(&format!("https://localhost:{}/", addr.port()))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_client_certificate() -> Result<()> {
+    let handler = HttpHandler { check: &Check::default() };
+    let (certificate, key) = certificate("client")?;
+
+    let addr = tls_server(true, "200 OK").await?;
+    let spec = Http {
+      verify_tls: false,
+      ..spec(&format!("https://localhost:{}/", addr.port()))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+
+    let addr = tls_server(true, "200 OK").await?;
+    let spec = Http {
+      url: format!("https://localhost:{}/", addr.port()),
+      client_cert: Some(String::from_utf8(certificate.to_pem()?)?),
+      client_key: Some(String::from_utf8(key.rsa()?.private_key_to_pem()?)?),
+      ..spec
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_codes() -> Result<()> {
+    let handler = HttpHandler { check: &Check::default() };
+
+    for (codes, status) in [(vec!["2xx"], OK), (vec!["200-201"], OK), (vec!["200", "202"], CRITICAL)] {
+      let addr = server("201 Created").await?;
+      let spec = Http {
+        codes: codes.into_iter().map(ToString::to_string).collect::<Vec<_>>().into(),
+        ..spec(&format!("http://{addr}/"))
+      };
+
+      let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+      assert_eq!(result.status, status);
+    }
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_assertions() -> Result<()> {
+    let addr = server("200 OK\r\nx-served-by: edge-1").await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      response_headers: HttpHeaders(HashMap::from([("x-served-by".to_string(), "edge".to_string())])),
+      regex: Some(r"^GET /\w+ HTTP".to_string()),
+      not_content: Some("Exception".to_string()),
+      not_regex: Some(r"(?i)error \d+".to_string()),
+      ..spec(&format!("http://{addr}/status"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_failure_report() -> Result<()> {
+    let addr = server("200 OK\r\nx-served-by: edge-1").await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      code: Some(201),
+      response_headers: HttpHeaders(HashMap::from([("x-served-by".to_string(), "origin".to_string())])),
+      regex: Some("^POST".to_string()),
+      not_content: Some("GET".to_string()),
+      not_regex: Some(r"HTTP/1\.[01]".to_string()),
+      ..spec(&format!("http://{addr}/status"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+    let failures = result.message.split("; ").collect::<Vec<_>>();
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(
+      failures,
+      vec![
+        "status code was 200",
+        "header `x-served-by` was `edge-1`",
+        "content does not match `^POST`",
+        "content contains `GET`",
+        r"content matches `HTTP/1\.[01]`",
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_json_schema() -> Result<()> {
+    let handler = HttpHandler { check: &Check::default() };
+    let schema = serde_json::json!({
+      "type": "object",
+      "required": ["status", "checks"],
+      "properties": {
+        "status": { "enum": ["ok"] },
+        "checks": { "type": "array" },
+      },
+    });
+
+    let addr = testing::serve(|_| ("200 OK", r#"{"status": "ok", "checks": []}"#)).await?;
+    let spec = Http {
+      json_schema: Some(Json(schema)),
+      ..spec(&format!("http://{addr}/"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    let addr = testing::serve(|_| ("200 OK", r#"{"status": "degraded"}"#)).await?;
+    let result = handler
+      .run(
+        &Http {
+          url: format!("http://{addr}/"),
+          ..spec.clone()
+        },
+        CONTROLLER_ID,
+        Stash::new(),
+      )
+      .await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("JSON schema validation failed: "));
+    assert!(result.message.contains(r#""checks" is a required property"#));
+    assert!(result.message.contains(r#""degraded" is not one of ["ok"] at /status"#));
+
+    let addr = testing::serve(|_| ("200 OK", "<html></html>")).await?;
+    let result = handler
+      .run(
+        &Http {
+          url: format!("http://{addr}/"),
+          ..spec
+        },
+        CONTROLLER_ID,
+        Stash::new(),
+      )
+      .await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(result.message, "content is not valid JSON");
+
+    Ok(())
+  }
+
+  /// Serve redirects from `/start` to `/www`, then to `/final`.
+  async fn redirector() -> Result<SocketAddr> {
+    testing::serve(|request| {
+      let head = match request.path() {
+        "/start" => format!("301 Moved Permanently\r\nlocation: http://{}/www", request.header("host").unwrap_or_default()),
+        "/www" => "302 Found\r\nlocation: /final".to_string(),
+        _ => "200 OK".to_string(),
+      };
+
+      (head, "")
+    })
+    .await
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_redirects() -> Result<()> {
+    let addr = redirector().await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      max_hops: Some(2),
+      final_url: Some(format!("http://{addr}/final")),
+      final_host: Some("127.0.0.1".to_string()),
+      no_downgrade: true,
+      code: Some(200),
+      ..spec(&format!("http://{addr}/start"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert_eq!(result.message, format!("http://{addr}/start -> http://{addr}/www -> http://{addr}/final"));
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_redirects_failures() -> Result<()> {
+    let addr = redirector().await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      max_hops: Some(1),
+      final_url: Some(format!("http://{addr}/www")),
+      final_host: Some("www.example.com".to_string()),
+      ..spec(&format!("http://{addr}/start"))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(
+      result.message,
+      format!(
+        "redirected 2 times, expected at most 1; final URL was http://{addr}/final, expected http://{addr}/www; final host was 127.0.0.1, expected www.example.com \
+         (http://{addr}/start -> http://{addr}/www -> http://{addr}/final)"
+      )
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_redirects_downgrade() -> Result<()> {
+    let target = server("200 OK").await?;
+    let addr = tls_server(false, &format!("301 Moved Permanently\r\nlocation: http://{target}/")).await?;
+
+    let handler = HttpHandler { check: &Check::default() };
+    let spec = Http {
+      verify_tls: false,
+      no_downgrade: true,
+      ..spec(&format!("https://localhost:{}/", addr.port()))
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result
+      .message
+      .starts_with(&format!("redirect from https://localhost:{}/ to http://{}/ downgrades to http", addr.port(), target)));
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_redirects_credentials() -> Result<()> {
+    let target = server("200 OK").await?;
+    let head = format!("302 Found\r\nlocation: http://{target}/");
+    let addr = testing::serve(move |_| (head.clone(), "")).await?;
+
+    let spec = Http {
+      username: Some("monitor".to_string()),
+      password: Some("secret".to_string()),
+      headers: HttpHeaders(HashMap::from([
+        ("Authorization".to_string(), "Bearer secret".to_string()),
+        ("x-custom".to_string(), "kept".to_string()),
+      ])),
+      max_hops: Some(1),
+      ..spec(&format!("http://{addr}/"))
+    };
+
+    let response = super::request(&super::agent(&spec, std::time::Duration::from_secs(5))?, &spec)?;
+    let echo = response.body.to_lowercase();
+
+    assert_eq!(response.hops, vec![format!("http://{addr}/"), format!("http://{target}/")]);
+    assert!(!echo.contains("authorization"));
+    assert!(echo.contains("x-custom: kept"));
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn handler_http_headers() {
     let mut headers = HashMap::default();
@@ -143,16 +902,9 @@ mod tests {
 
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://httpbin.org/headers".to_string(),
       headers: HttpHeaders(headers),
-      timeout: None,
-      code: None,
       content: Some(r#""Lorem": "ipsum""#.to_string()),
-      digest: None,
-      json_query: None,
-      duration: None,
+      ..spec("https://httpbin.org/headers")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -167,16 +919,10 @@ mod tests {
   async fn handler_http_ok() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://httpbin.org/user-agent".to_string(),
-      headers: Default::default(),
-      timeout: None,
       code: Some(200),
       content: Some(r#""user-agent": "defcon""#.to_string()),
       digest: Some("2d3cb778b29b905457d6b87b3a4258202bfdbe883251523f7e479e5505b7df6bedbc25f5061e5a677e9e92bf3560a993d5cd88ba5918cc1b5bed1db23b060c84".to_string()),
-      json_query: None,
-      duration: None,
+      ..spec("https://httpbin.org/user-agent")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -191,16 +937,8 @@ mod tests {
   async fn handler_http_invalid_status_below_400() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://httpbin.org/status/300".to_string(),
-      headers: Default::default(),
-      timeout: None,
       code: Some(301),
-      content: None,
-      digest: None,
-      json_query: None,
-      duration: None,
+      ..spec("https://httpbin.org/status/300")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -215,16 +953,8 @@ mod tests {
   async fn handler_http_invalid_status_above_400() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://httpbin.org/status/400".to_string(),
-      headers: Default::default(),
-      timeout: None,
       code: Some(201),
-      content: None,
-      digest: None,
-      json_query: None,
-      duration: None,
+      ..spec("https://httpbin.org/status/400")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -239,16 +969,8 @@ mod tests {
   async fn handler_http_invalid_content() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "http://httpbin.org/anything/helloworld".to_string(),
-      headers: Default::default(),
-      code: None,
-      timeout: None,
       content: Some("INVALIDCONTENT".to_string()),
-      digest: None,
-      json_query: None,
-      duration: None,
+      ..spec("http://httpbin.org/anything/helloworld")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -263,16 +985,8 @@ mod tests {
   async fn handler_http_invalid_digest() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://httpbin.org/status/200".to_string(),
-      headers: Default::default(),
-      code: None,
-      timeout: None,
-      content: None,
       digest: Some("INVALIDDIGEST".to_string()),
-      json_query: None,
-      duration: None,
+      ..spec("https://httpbin.org/status/200")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -288,16 +1002,10 @@ mod tests {
   async fn handler_http_valid_json_query() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://login.microsoftonline.com/common/v2.0/.well-known/openid-configuration".to_string(),
-      headers: Default::default(),
       timeout: Some(Duration::from(1)),
       code: Some(200),
-      content: None,
-      digest: None,
       json_query: Some(r#".claims_supported | contains(["email"])"#.to_string()),
-      duration: None,
+      ..spec("https://login.microsoftonline.com/common/v2.0/.well-known/openid-configuration")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -312,16 +1020,10 @@ mod tests {
   async fn handler_http_invalid_json_query() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://login.microsoftonline.com/common/v2.0/.well-known/openid-configuration".to_string(),
-      headers: Default::default(),
       timeout: Some(Duration::from(1)),
       code: Some(200),
-      content: None,
-      digest: None,
       json_query: Some(r#".issuer == "github.com""#.to_string()),
-      duration: None,
+      ..spec("https://login.microsoftonline.com/common/v2.0/.well-known/openid-configuration")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -335,16 +1037,8 @@ mod tests {
   async fn handler_http_duration_ok() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://httpbin.org/delay/1".to_string(),
-      headers: Default::default(),
-      code: None,
-      timeout: None,
-      content: None,
-      digest: None,
-      json_query: None,
       duration: Some(Duration::from(5)),
+      ..spec("https://httpbin.org/delay/1")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -358,16 +1052,8 @@ mod tests {
   async fn handler_http_duration_too_long() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "https://httpbin.org/delay/3".to_string(),
-      headers: Default::default(),
-      code: None,
-      timeout: None,
-      content: None,
-      digest: None,
-      json_query: None,
       duration: Some(Duration::from(1)),
+      ..spec("https://httpbin.org/delay/3")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -382,16 +1068,9 @@ mod tests {
   async fn handler_http_timeout() {
     let handler = HttpHandler { check: &Check::default() };
     let spec = Http {
-      id: 0,
-      check_id: 0,
-      url: "http://192.0.2.1".to_string(),
-      headers: Default::default(),
       timeout: Some(Duration::from(1)),
       code: Some(200),
-      content: None,
-      digest: None,
-      json_query: None,
-      duration: None,
+      ..spec("http://192.0.2.1")
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -0,0 +1,293 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use cookie_store::CookieStore;
+use regex::Regex;
+use sqlx::MySqlConnection;
+
+use crate::{
+  config::Config,
+  handlers::{
+    http::{self, Response},
+    Handler,
+  },
+  model::{
+    specs::{Http, HttpExtraction, HttpFlow, HttpHeaders},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+pub struct HttpFlowHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for HttpFlowHandler<'_> {
+  type Spec = HttpFlow;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = HttpFlow::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &HttpFlow, site: &str, _stash: Stash) -> Result<Event> {
+    let (status, message) = match flow(spec) {
+      Ok(timings) => (OK, timings.join(", ")),
+      Err(failure) => (CRITICAL, failure),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Run every step in order, and return their timings, or the reason for which the first broken step failed.
+fn flow(spec: &HttpFlow) -> Result<Vec<String>, String> {
+  let mut variables = HashMap::new();
+  let mut timings = Vec::with_capacity(spec.steps.len());
+
+  // Each step can have its own proxy, certificates or timeout, so they do not share an agent, but cookies carry over
+  // from one step to the next as they would in a browser session.
+  let mut cookies = CookieStore::default();
+
+  for (index, step) in spec.steps.iter().enumerate() {
+    let label = match step.name {
+      Some(ref name) => format!("step `{name}`"),
+      None => format!("step {}", index + 1),
+    };
+
+    let request = substitute(&step.request, &variables);
+    let timeout = request.timeout.unwrap_or_else(|| Duration::from(5));
+
+    let agent = http::builder(&request, *timeout)
+      .map(|builder| builder.cookie_store(cookies.clone()).build())
+      .map_err(|err| format!("{label} failed: {err:#}"))?;
+
+    let response = http::request(&agent, &request).map_err(|err| format!("{label} failed: {err}"))?;
+    cookies = agent.cookie_store().clone();
+    let failures = http::assertions(&request, &response);
+
+    if !failures.is_empty() {
+      return Err(format!("{label} failed after {}ms: {}", response.duration.as_millis(), failures.join("; ")));
+    }
+
+    for extraction in &step.extract {
+      let value = extract(extraction, &response).map_err(|err| format!("{label} failed: {err}"))?;
+
+      variables.insert(extraction.variable.clone(), value);
+    }
+
+    timings.push(format!("{label} took {}ms", response.duration.as_millis()));
+  }
+
+  Ok(timings)
+}
+
+/// Replace `{{variable}}` placeholders in the URL, headers, body and token of a request.
+fn substitute(request: &Http, variables: &HashMap<String, String>) -> Http {
+  let replace = |text: &str| variables.iter().fold(text.to_string(), |text, (variable, value)| text.replace(&format!("{{{{{variable}}}}}"), value));
+
+  Http {
+    url: replace(&request.url),
+    headers: HttpHeaders(request.headers.iter().map(|(header, value)| (header.clone(), replace(value))).collect()),
+    body: request.body.as_deref().map(replace),
+    token: request.token.as_deref().map(replace),
+    ..request.clone()
+  }
+}
+
+fn extract(extraction: &HttpExtraction, response: &Response) -> Result<String, String> {
+  let variable = &extraction.variable;
+
+  match (&extraction.json, &extraction.regex, &extraction.header) {
+    (Some(pointer), None, None) => {
+      let document = serde_json::from_str::<serde_json::Value>(&response.body).map_err(|_| format!("could not extract `{variable}`: content is not valid JSON"))?;
+
+      match document.pointer(pointer) {
+        Some(serde_json::Value::String(value)) => Ok(value.clone()),
+        Some(value) => Ok(value.to_string()),
+        None => Err(format!("could not extract `{variable}`: nothing found at {pointer}")),
+      }
+    }
+
+    (None, Some(regex), None) => {
+      let regex = Regex::new(regex).map_err(|_| format!("could not extract `{variable}`: invalid regex `{regex}`"))?;
+      let captures = regex
+        .captures(&response.body)
+        .ok_or_else(|| format!("could not extract `{variable}`: content does not match `{regex}`"))?;
+
+      // Extract the first capture group if there is one, or the whole match.
+      Ok(captures.get(1).or_else(|| captures.get(0)).map(|value| value.as_str().to_string()).unwrap_or_default())
+    }
+
+    // Responses can set several cookies, whose pairs are joined to be sent back as a `cookie` header.
+    (None, None, Some(header)) if header.eq_ignore_ascii_case("set-cookie") => match response.cookies.is_empty() {
+      true => Err(format!("could not extract `{variable}`: header `{header}` is missing")),
+      false => Ok(response.cookies.iter().map(|cookie| cookie.split(';').next().unwrap_or_default().trim()).collect::<Vec<_>>().join("; ")),
+    },
+
+    (None, None, Some(header)) => response
+      .headers
+      .get(&header.to_lowercase())
+      .cloned()
+      .ok_or_else(|| format!("could not extract `{variable}`: header `{header}` is missing")),
+
+    _ => Err(format!("could not extract `{variable}`: exactly one of `json`, `regex` or `header` must be set")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::SocketAddr;
+
+  use anyhow::Result;
+
+  use super::{Handler, HttpFlowHandler};
+  use crate::{
+    config::CONTROLLER_ID,
+    handlers::testing,
+    model::{specs::HttpFlow, status::*, Check},
+    stash::Stash,
+  };
+
+  /// Serve a tiny API requiring to log in to retrieve a profile.
+  async fn server() -> Result<SocketAddr> {
+    testing::serve(|request| {
+      let authorization = request.header("authorization").unwrap_or_default();
+
+      match request.line() {
+        "POST /login HTTP/1.1" if request.body.contains(r#""password":"secret""#) => ("200 OK\r\nx-session: 42", r#"{"token": "abc123"}"#),
+        "GET /sessions/42/profile HTTP/1.1" if authorization == "Bearer abc123" => ("200 OK", r#"{"name": "lorem"}"#),
+        "POST /logout HTTP/1.1" => ("204 No Content", ""),
+        _ => ("401 Unauthorized", ""),
+      }
+    })
+    .await
+  }
+
+  fn flow(addr: SocketAddr, token: &str) -> Result<HttpFlow> {
+    let spec = serde_json::json!({
+      "steps": [
+        {
+          "name": "login",
+          "url": format!("http://{addr}/login"),
+          "method": "POST",
+          "body": r#"{"username":"lorem","password":"secret"}"#,
+          "code": 200,
+          "extract": [
+            { "variable": "token", "json": token },
+            { "variable": "session", "header": "X-Session" },
+          ],
+        },
+        {
+          "name": "profile",
+          "url": format!("http://{addr}/sessions/{{{{session}}}}/profile"),
+          "headers": { "authorization": "Bearer {{token}}" },
+          "code": 200,
+          "extract": [{ "variable": "name", "regex": r#""name": "(\w+)""# }],
+        },
+        {
+          "url": format!("http://{addr}/logout"),
+          "method": "POST",
+          "body": "{{name}}",
+          "codes": ["2xx"],
+        },
+      ],
+    });
+
+    Ok(serde_json::from_value(spec)?)
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_flow_ok() -> Result<()> {
+    let addr = server().await?;
+
+    let handler = HttpFlowHandler { check: &Check::default() };
+    let result = handler.run(&flow(addr, "/token")?, CONTROLLER_ID, Stash::new()).await?;
+    let timings = result.message.split(", ").collect::<Vec<_>>();
+
+    assert_eq!(result.status, OK);
+    assert_eq!(timings.len(), 3);
+    assert!(timings[0].starts_with("step `login` took "));
+    assert!(timings[1].starts_with("step `profile` took "));
+    assert!(timings[2].starts_with("step 3 took "));
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_flow_extraction() -> Result<()> {
+    let addr = server().await?;
+
+    let handler = HttpFlowHandler { check: &Check::default() };
+    let result = handler.run(&flow(addr, "/access_token")?, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(result.message, "step `login` failed: could not extract `token`: nothing found at /access_token");
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_flow_assertion() -> Result<()> {
+    let addr = server().await?;
+
+    let mut spec = flow(addr, "/token")?;
+    spec.steps[0].extract.clear();
+
+    let handler = HttpFlowHandler { check: &Check::default() };
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("step `profile` failed after "));
+    assert!(result.message.ends_with("ms: status code was 401"));
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_http_flow_cookies() -> Result<()> {
+    let addr = testing::serve(|request| {
+      let cookie = request.header("cookie").unwrap_or_default();
+
+      match request.line() {
+        "POST /login HTTP/1.1" => ("200 OK\r\nset-cookie: session=42; Path=/; HttpOnly\r\nset-cookie: theme=dark", ""),
+        "GET /account HTTP/1.1" if cookie.contains("session=42") => ("200 OK", ""),
+        "POST /cookies HTTP/1.1" if request.body == "session=42; theme=dark" => ("200 OK", ""),
+        _ => ("401 Unauthorized", ""),
+      }
+    })
+    .await?;
+
+    let spec = serde_json::from_value(serde_json::json!({
+      "steps": [
+        {
+          "url": format!("http://{addr}/login"),
+          "method": "POST",
+          "code": 200,
+          "extract": [{ "variable": "cookies", "header": "Set-Cookie" }],
+        },
+        { "url": format!("http://{addr}/account"), "code": 200 },
+        { "url": format!("http://{addr}/cookies"), "method": "POST", "body": "{{cookies}}", "code": 200 },
+      ],
+    }))?;
+
+    let handler = HttpFlowHandler { check: &Check::default() };
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK, "{}", result.message);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,492 @@
+use std::{
+  net::{SocketAddr, ToSocketAddrs},
+  sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::MySqlConnection;
+use tokio::{
+  io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+  net::TcpStream,
+  time,
+};
+
+use crate::{
+  config::Config,
+  handlers::{starttls, Handler},
+  model::{
+    specs::{MailAccess, MailProtocol, MailSecurity},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+pub struct MailAccessHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for MailAccessHandler<'_> {
+  type Spec = MailAccess;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = MailAccess::for_check(conn, self.check).await.context("no spec found")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &MailAccess, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(10));
+
+    let addr = format!("{}:{}", spec.host, spec.port);
+    let addr = addr.to_socket_addrs().context("could not parse host")?.next().ok_or_else(|| anyhow!("could not parse host"))?;
+
+    // Specs stored before they were validated could still hold assertions without credentials.
+    let result = match spec.validate() {
+      Ok(()) => time::timeout(*timeout, session(spec, addr)).await,
+      Err(err) => Ok(Err(err)),
+    };
+
+    let (status, message) = match result {
+      Ok(Ok(Some(count))) => match (spec.min_messages, spec.max_messages) {
+        (Some(min), _) if count < min as u64 => (CRITICAL, format!("mailbox contains {count} messages, expected at least {min}")),
+        (_, Some(max)) if count > max as u64 => (CRITICAL, format!("mailbox contains {count} messages, expected at most {max}")),
+        _ => (OK, format!("mailbox contains {count} messages")),
+      },
+
+      Ok(Ok(None)) => (OK, String::new()),
+      Ok(Err(err)) => (CRITICAL, format!("{err:#}")),
+      Err(err) => (CRITICAL, err.to_string()),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+async fn session(spec: &MailAccess, addr: SocketAddr) -> Result<Option<u64>> {
+  let stream = TcpStream::connect(addr).await?;
+
+  match spec.security {
+    MailSecurity::Plain => {
+      let mut stream = BufReader::new(stream);
+
+      greeting(spec.protocol, &mut stream).await?;
+      authenticate(spec, &mut stream).await
+    }
+
+    MailSecurity::Tls => {
+      let mut stream = BufReader::new(starttls::upgrade(stream, &spec.host).await?);
+
+      greeting(spec.protocol, &mut stream).await?;
+      authenticate(spec, &mut stream).await
+    }
+
+    MailSecurity::StartTls => {
+      let mut stream = BufReader::new(stream);
+
+      greeting(spec.protocol, &mut stream).await?;
+
+      match spec.protocol {
+        MailProtocol::Imap => imap::starttls(&mut stream).await?,
+        MailProtocol::Pop3 => pop3::starttls(&mut stream).await?,
+      }
+
+      let mut stream = BufReader::new(starttls::upgrade(stream.into_inner(), &spec.host).await?);
+
+      authenticate(spec, &mut stream).await
+    }
+  }
+}
+
+async fn greeting<S>(protocol: MailProtocol, stream: &mut S) -> Result<()>
+where
+  S: AsyncBufRead + AsyncWrite + Unpin,
+{
+  match protocol {
+    MailProtocol::Imap => imap::greeting(stream).await,
+    MailProtocol::Pop3 => pop3::greeting(stream).await,
+  }
+}
+
+async fn authenticate<S>(spec: &MailAccess, stream: &mut S) -> Result<Option<u64>>
+where
+  S: AsyncBufRead + AsyncWrite + Unpin,
+{
+  match spec.protocol {
+    MailProtocol::Imap => imap::session(spec, stream).await,
+    MailProtocol::Pop3 => pop3::session(spec, stream).await,
+  }
+}
+
+async fn line<S>(stream: &mut S) -> Result<String>
+where
+  S: AsyncBufRead + Unpin,
+{
+  let mut line = String::new();
+
+  if stream.read_line(&mut line).await? == 0 {
+    return Err(anyhow!("connection closed by server"));
+  }
+
+  Ok(line.trim_end().to_string())
+}
+
+async fn send<S>(stream: &mut S, command: &str) -> Result<()>
+where
+  S: AsyncWrite + Unpin,
+{
+  stream.write_all(format!("{command}\r\n").as_bytes()).await?;
+  stream.flush().await?;
+
+  Ok(())
+}
+
+mod imap {
+  use anyhow::Result;
+  use tokio::io::{AsyncBufRead, AsyncWrite};
+
+  use super::{line, send};
+  use crate::model::specs::MailAccess;
+
+  pub async fn greeting<S>(stream: &mut S) -> Result<()>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    let greeting = line(stream).await?;
+
+    if greeting.starts_with("* OK") || greeting.starts_with("* PREAUTH") {
+      Ok(())
+    } else {
+      Err(anyhow!("unexpected greeting: {}", greeting))
+    }
+  }
+
+  pub async fn starttls<S>(stream: &mut S) -> Result<()>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    command(stream, "a0", "STARTTLS", "STARTTLS").await?;
+
+    Ok(())
+  }
+
+  pub async fn session<S>(spec: &MailAccess, stream: &mut S) -> Result<Option<u64>>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    if let Some(ref username) = spec.username {
+      let password = spec.password.as_deref().unwrap_or_default();
+
+      command(stream, "a1", &format!("LOGIN {} {}", quote(username), quote(password)), "LOGIN").await?;
+    }
+
+    let count = match spec.mailbox {
+      None if spec.min_messages.is_none() && spec.max_messages.is_none() => None,
+
+      ref mailbox => {
+        let mailbox = mailbox.as_deref().unwrap_or("INBOX");
+        let lines = command(stream, "a2", &format!("SELECT {}", quote(mailbox)), "SELECT").await?;
+
+        let count = lines.iter().find_map(|line| {
+          let mut tokens = line.split_whitespace();
+
+          match (tokens.next(), tokens.next(), tokens.next()) {
+            (Some("*"), Some(count), Some(keyword)) if keyword.eq_ignore_ascii_case("EXISTS") => count.parse::<u64>().ok(),
+            _ => None,
+          }
+        });
+
+        Some(count.ok_or_else(|| anyhow!("could not retrieve message count for {}", mailbox))?)
+      }
+    };
+
+    let _ = command(stream, "a3", "LOGOUT", "LOGOUT").await;
+
+    Ok(count)
+  }
+
+  async fn command<S>(stream: &mut S, tag: &str, command: &str, name: &str) -> Result<Vec<String>>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    send(stream, &format!("{tag} {command}")).await?;
+
+    let mut lines = Vec::new();
+
+    loop {
+      let line = line(stream).await?;
+
+      match line.strip_prefix(tag).and_then(|rest| rest.strip_prefix(' ')) {
+        Some(rest) => match rest.split_once(' ').unwrap_or((rest, "")) {
+          (status, _) if status.eq_ignore_ascii_case("OK") => return Ok(lines),
+          (_, message) => return Err(anyhow!("{} failed: {}", name, message)),
+        },
+
+        None => lines.push(line),
+      }
+    }
+  }
+
+  fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+  }
+}
+
+mod pop3 {
+  use anyhow::Result;
+  use tokio::io::{AsyncBufRead, AsyncWrite};
+
+  use super::{line, send};
+  use crate::model::specs::MailAccess;
+
+  pub async fn greeting<S>(stream: &mut S) -> Result<()>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    let greeting = line(stream).await?;
+
+    match greeting.starts_with("+OK") {
+      true => Ok(()),
+      false => Err(anyhow!("unexpected greeting: {}", greeting)),
+    }
+  }
+
+  pub async fn starttls<S>(stream: &mut S) -> Result<()>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    command(stream, "STLS", "STLS").await?;
+
+    Ok(())
+  }
+
+  pub async fn session<S>(spec: &MailAccess, stream: &mut S) -> Result<Option<u64>>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    let username = match spec.username {
+      Some(ref username) => username,
+
+      None => {
+        let _ = command(stream, "QUIT", "QUIT").await;
+
+        return Ok(None);
+      }
+    };
+
+    command(stream, &format!("USER {username}"), "USER").await?;
+    command(stream, &format!("PASS {}", spec.password.as_deref().unwrap_or_default()), "PASS").await?;
+
+    let count = match (&spec.mailbox, spec.min_messages, spec.max_messages) {
+      (None, None, None) => None,
+
+      _ => {
+        let response = command(stream, "STAT", "STAT").await?;
+        let count = response.split_whitespace().next().and_then(|count| count.parse::<u64>().ok());
+
+        Some(count.ok_or_else(|| anyhow!("could not retrieve message count"))?)
+      }
+    };
+
+    let _ = command(stream, "QUIT", "QUIT").await;
+
+    Ok(count)
+  }
+
+  async fn command<S>(stream: &mut S, command: &str, name: &str) -> Result<String>
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    send(stream, command).await?;
+
+    let response = line(stream).await?;
+
+    match response.strip_prefix("+OK") {
+      Some(rest) => Ok(rest.trim().to_string()),
+      None => Err(anyhow!("{} failed: {}", name, response.strip_prefix("-ERR").unwrap_or(&response).trim())),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::SocketAddr;
+
+  use anyhow::Result;
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+  };
+
+  use super::{Handler, MailAccessHandler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{
+      specs::{MailAccess, MailProtocol, MailSecurity},
+      status::*,
+      Check,
+    },
+    stash::Stash,
+  };
+
+  async fn server(protocol: MailProtocol, messages: u64) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let mut stream = BufReader::new(stream);
+
+      let greeting = match protocol {
+        MailProtocol::Imap => "* OK IMAP4rev1 ready\r\n",
+        MailProtocol::Pop3 => "+OK POP3 ready\r\n",
+      };
+
+      stream.write_all(greeting.as_bytes()).await.unwrap();
+
+      loop {
+        let mut line = String::new();
+
+        if stream.read_line(&mut line).await.unwrap() == 0 {
+          break;
+        }
+
+        let response = match (protocol, line.trim_end()) {
+          (MailProtocol::Imap, r#"a1 LOGIN "john" "secret""#) => "a1 OK LOGIN completed\r\n".to_string(),
+          (MailProtocol::Imap, command) if command.starts_with("a1 LOGIN") => "a1 NO [AUTHENTICATIONFAILED] Invalid credentials\r\n".to_string(),
+          (MailProtocol::Imap, r#"a2 SELECT "INBOX""#) => format!("* {messages} EXISTS\r\n* 0 RECENT\r\na2 OK [READ-WRITE] SELECT completed\r\n"),
+          (MailProtocol::Imap, "a3 LOGOUT") => "* BYE\r\na3 OK LOGOUT completed\r\n".to_string(),
+          (MailProtocol::Imap, command) => format!("{} BAD unknown command\r\n", command.split(' ').next().unwrap_or_default()),
+
+          (MailProtocol::Pop3, "USER john") => "+OK\r\n".to_string(),
+          (MailProtocol::Pop3, "PASS secret") => "+OK logged in\r\n".to_string(),
+          (MailProtocol::Pop3, command) if command.starts_with("PASS") => "-ERR invalid credentials\r\n".to_string(),
+          (MailProtocol::Pop3, "STAT") => format!("+OK {messages} 1024\r\n"),
+          (MailProtocol::Pop3, "QUIT") => "+OK bye\r\n".to_string(),
+          (MailProtocol::Pop3, _) => "-ERR unknown command\r\n".to_string(),
+        };
+
+        stream.write_all(response.as_bytes()).await.unwrap();
+      }
+    });
+
+    Ok(addr)
+  }
+
+  fn spec(protocol: MailProtocol, addr: SocketAddr, password: &str) -> MailAccess {
+    MailAccess {
+      id: 0,
+      check_id: 0,
+      protocol,
+      host: addr.ip().to_string(),
+      port: addr.port(),
+      security: MailSecurity::Plain,
+      username: Some("john".to_string()),
+      password: Some(password.to_string()),
+      mailbox: Some("INBOX".to_string()),
+      min_messages: None,
+      max_messages: None,
+      timeout: Some(1.into()),
+    }
+  }
+
+  #[tokio::test]
+  async fn handler_mail_access_imap_ok() -> Result<()> {
+    let addr = server(MailProtocol::Imap, 12).await?;
+
+    let handler = MailAccessHandler { check: &Check::default() };
+    let result = handler.run(&spec(MailProtocol::Imap, addr, "secret"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert_eq!(&result.message, "mailbox contains 12 messages");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_mail_access_imap_invalid_credentials() -> Result<()> {
+    let addr = server(MailProtocol::Imap, 12).await?;
+
+    let handler = MailAccessHandler { check: &Check::default() };
+    let result = handler.run(&spec(MailProtocol::Imap, addr, "wrong"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "LOGIN failed: [AUTHENTICATIONFAILED] Invalid credentials");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_mail_access_imap_too_many_messages() -> Result<()> {
+    let addr = server(MailProtocol::Imap, 120).await?;
+
+    let handler = MailAccessHandler { check: &Check::default() };
+    let spec = MailAccess {
+      max_messages: Some(100),
+      ..spec(MailProtocol::Imap, addr, "secret")
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "mailbox contains 120 messages, expected at most 100");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_mail_access_pop3_ok() -> Result<()> {
+    let addr = server(MailProtocol::Pop3, 3).await?;
+
+    let handler = MailAccessHandler { check: &Check::default() };
+    let spec = MailAccess {
+      min_messages: Some(1),
+      ..spec(MailProtocol::Pop3, addr, "secret")
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert_eq!(&result.message, "mailbox contains 3 messages");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_mail_access_pop3_invalid_credentials() -> Result<()> {
+    let addr = server(MailProtocol::Pop3, 3).await?;
+
+    let handler = MailAccessHandler { check: &Check::default() };
+    let result = handler.run(&spec(MailProtocol::Pop3, addr, "wrong"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "PASS failed: invalid credentials");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_mail_access_count_without_credentials() {
+    let handler = MailAccessHandler { check: &Check::default() };
+    let spec = MailAccess {
+      username: None,
+      min_messages: Some(1),
+      ..spec(MailProtocol::Imap, "127.0.0.1:143".parse().unwrap(), "")
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await.unwrap();
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(result.message, "`min_messages` and `max_messages` require a `username`");
+  }
+}
@@ -1,15 +1,36 @@
 mod app_store;
+mod crawl;
+mod database;
 mod deadmanswitch;
 mod dns;
+mod dns_consistency;
+mod dnssec;
+mod exec;
+mod external;
+mod grpc;
 mod http;
+mod http_flow;
+mod mail_access;
+mod ntp;
 #[cfg(feature = "ping")]
 mod ping;
 mod play_store;
+mod prometheus;
 #[cfg(feature = "python")]
 mod python;
+mod script;
+mod security_headers;
+mod smtp;
+mod ssh;
+mod starttls;
 mod tcp;
+#[cfg(test)]
+pub(crate) mod testing;
 mod tls;
 mod udp;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod websocket;
 mod whois;
 
 use kvlogger::*;
@@ -24,11 +45,15 @@ use sqlx::MySqlConnection;
 pub use crate::handlers::ping::PingHandler;
 #[cfg(feature = "python")]
 pub use crate::handlers::python::PythonHandler;
+#[cfg(feature = "wasm")]
+pub use crate::handlers::wasm::WasmHandler;
 pub use crate::{
   config::Config,
   handlers::{
-    app_store::AppStoreHandler, deadmanswitch::DeadManSwitchHandler, dns::DnsHandler, http::HttpHandler, play_store::PlayStoreHandler, tcp::TcpHandler, tls::TlsHandler, udp::UdpHandler,
-    whois::WhoisHandler,
+    app_store::AppStoreHandler, crawl::CrawlHandler, database::DatabaseHandler, deadmanswitch::DeadManSwitchHandler, dns::DnsHandler, dns_consistency::DnsConsistencyHandler, dnssec::DnssecHandler,
+    exec::ExecHandler, external::ExternalHandler, grpc::GrpcHandler, http::HttpHandler, http_flow::HttpFlowHandler, mail_access::MailAccessHandler, ntp::NtpHandler, play_store::PlayStoreHandler,
+    prometheus::PrometheusHandler, script::ScriptHandler, security_headers::SecurityHeadersHandler, smtp::SmtpHandler, ssh::SshHandler, tcp::TcpHandler, tls::TlsHandler, udp::UdpHandler,
+    websocket::WebSocketHandler, whois::WhoisHandler,
   },
   inhibitor::Inhibitor,
   model::{Check, Event, Outage, SiteOutage, Timeline},
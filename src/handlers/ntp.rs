@@ -0,0 +1,252 @@
+use std::{
+  net::{SocketAddr, ToSocketAddrs},
+  sync::Arc,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::MySqlConnection;
+use tokio::{net::UdpSocket, time};
+
+use crate::{
+  config::Config,
+  handlers::Handler,
+  model::{specs::Ntp, status::*, Check, Duration, Event},
+  stash::Stash,
+};
+
+/// Seconds between the NTP era (1900-01-01) and the UNIX epoch.
+const NTP_EPOCH_OFFSET: f64 = 2_208_988_800.0;
+/// Seconds in an NTP era, after which the 32-bit seconds of timestamps wrap around (next in 2036).
+const NTP_ERA: f64 = 4_294_967_296.0;
+const STRATUM_UNSYNCHRONIZED: u8 = 16;
+
+pub struct NtpHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for NtpHandler<'_> {
+  type Spec = Ntp;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Ntp::for_check(conn, self.check).await.context("no spec found")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Ntp, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+
+    let addr = format!("{}:{}", spec.host, spec.port);
+    let addr = addr.to_socket_addrs().context("could not parse host")?.next().ok_or_else(|| anyhow!("could not parse host"))?;
+
+    let (status, message) = match time::timeout(*timeout, query(addr)).await {
+      Ok(Ok(Sample { stratum, .. })) if stratum == 0 || stratum >= STRATUM_UNSYNCHRONIZED => (CRITICAL, format!("server is not synchronized (stratum {stratum})")),
+
+      Ok(Ok(Sample { stratum, offset })) => {
+        let millis = offset.abs() * 1000.0;
+        let message = format!("offset is {:+.1}ms at stratum {stratum}", offset * 1000.0);
+
+        match (spec.critical_ms, spec.warning_ms) {
+          (Some(critical), _) if millis > critical as f64 => (CRITICAL, message),
+          (_, Some(warning)) if millis > warning as f64 => (WARNING, message),
+          _ => (OK, message),
+        }
+      }
+
+      Ok(Err(err)) => (CRITICAL, format!("{err:#}")),
+      Err(err) => (CRITICAL, err.to_string()),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+struct Sample {
+  stratum: u8,
+  offset: f64,
+}
+
+async fn query(addr: SocketAddr) -> Result<Sample> {
+  let bind = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+  let socket = UdpSocket::bind(bind).await.context("could not open socket")?;
+
+  socket.connect(addr).await.context("could not connect socket")?;
+
+  // LI = 0, VN = 4, Mode = 3 (client), with our clock in the transmit timestamp.
+  let mut request = [0u8; 48];
+  request[0] = 0x23;
+
+  let originate = now();
+  request[40..48].copy_from_slice(&encode(originate));
+
+  socket.send(&request).await.context("could not send request")?;
+
+  let mut response = [0u8; 48];
+  let len = socket.recv(&mut response).await.context("could not receive response")?;
+  let destination = now();
+
+  if len < 48 {
+    return Err(anyhow!("response is too short ({len} bytes)"));
+  }
+
+  if response[0] & 0x07 != 4 {
+    return Err(anyhow!("response is not a server reply"));
+  }
+
+  if response[24..32] != request[40..48] {
+    return Err(anyhow!("response does not match the request"));
+  }
+
+  let receive = decode(&response[32..40], originate);
+  let transmit = decode(&response[40..48], originate);
+
+  let stratum = if response[0] >> 6 == 3 { STRATUM_UNSYNCHRONIZED } else { response[1] };
+  let offset = ((receive - originate) + (transmit - destination)) / 2.0;
+
+  Ok(Sample { stratum, offset })
+}
+
+/// Current time in seconds since the NTP era.
+fn now() -> f64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs_f64()).unwrap_or_default() + NTP_EPOCH_OFFSET
+}
+
+fn encode(timestamp: f64) -> [u8; 8] {
+  let seconds = timestamp.trunc().rem_euclid(NTP_ERA) as u32;
+  let fraction = (timestamp.fract() * 4_294_967_296.0) as u32;
+
+  let mut buf = [0u8; 8];
+  buf[..4].copy_from_slice(&seconds.to_be_bytes());
+  buf[4..].copy_from_slice(&fraction.to_be_bytes());
+
+  buf
+}
+
+/// Decode a timestamp in the era which brings it closest to the reference, since timestamps do not carry their era.
+fn decode(buf: &[u8], reference: f64) -> f64 {
+  let seconds = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64;
+  let fraction = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]) as f64;
+  let era = ((reference - seconds) / NTP_ERA).round();
+
+  era * NTP_ERA + seconds + fraction / 4_294_967_296.0
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::SocketAddr;
+
+  use anyhow::Result;
+  use tokio::net::UdpSocket;
+
+  use super::{decode, encode, now, Handler, NtpHandler, NTP_ERA};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::Ntp, status::*, Check},
+    stash::Stash,
+  };
+
+  async fn server(stratum: u8, skew: f64) -> Result<SocketAddr> {
+    let server = UdpSocket::bind("127.0.0.1:0").await?;
+    let addr = server.local_addr()?;
+
+    tokio::spawn(async move {
+      let mut request = [0u8; 48];
+      let (_, peer) = server.recv_from(&mut request).await.unwrap();
+
+      let mut response = [0u8; 48];
+      response[0] = 0x24;
+      response[1] = stratum;
+      response[24..32].copy_from_slice(&request[40..48]);
+      response[32..40].copy_from_slice(&encode(now() + skew));
+      response[40..48].copy_from_slice(&encode(now() + skew));
+
+      server.send_to(&response, &peer).await.unwrap();
+    });
+
+    Ok(addr)
+  }
+
+  fn spec(addr: SocketAddr) -> Ntp {
+    Ntp {
+      id: 0,
+      check_id: 0,
+      host: addr.ip().to_string(),
+      port: addr.port(),
+      warning_ms: Some(500),
+      critical_ms: Some(5000),
+      timeout: Some(1.into()),
+    }
+  }
+
+  #[tokio::test]
+  async fn handler_ntp_ok() -> Result<()> {
+    let addr = server(2, 0.0).await?;
+
+    let handler = NtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert!(result.message.ends_with("at stratum 2"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_ntp_warning() -> Result<()> {
+    let addr = server(2, 2.0).await?;
+
+    let handler = NtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, WARNING);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_ntp_critical() -> Result<()> {
+    let addr = server(2, -10.0).await?;
+
+    let handler = NtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("offset is -"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_ntp_unsynchronized() -> Result<()> {
+    let addr = server(16, 0.0).await?;
+
+    let handler = NtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "server is not synchronized (stratum 16)");
+
+    Ok(())
+  }
+
+  #[test]
+  fn era_rollover() {
+    let timestamp = NTP_ERA + 10.5;
+
+    assert_eq!(encode(timestamp)[..4], [0, 0, 0, 10]);
+    assert_eq!(decode(&encode(timestamp), NTP_ERA - 5.0), timestamp);
+    assert_eq!(decode(&encode(timestamp), NTP_ERA + 5.0), timestamp);
+    assert_eq!(decode(&encode(NTP_ERA - 5.0), NTP_ERA + 10.5), NTP_ERA - 5.0);
+  }
+}
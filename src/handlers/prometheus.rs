@@ -0,0 +1,294 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sqlx::MySqlConnection;
+
+use crate::{
+  config::Config,
+  handlers::{http, Handler},
+  model::{
+    specs::{Http, Prometheus},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+static THRESHOLD: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*(?:([a-zA-Z_:][a-zA-Z0-9_:]*)\s*)?(>=|<=|==|!=|>|<)\s*(\S+)\s*$").unwrap());
+
+/// Sample parsed from a line of the text exposition format.
+#[derive(Debug, PartialEq)]
+struct Sample {
+  name: String,
+  labels: Vec<(String, String)>,
+  value: f64,
+}
+
+pub struct PrometheusHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for PrometheusHandler<'_> {
+  type Spec = Prometheus;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Prometheus::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Prometheus, site: &str, _stash: Stash) -> Result<Event> {
+    let (status, message) = match scrape(spec) {
+      Ok(value) => {
+        let selector = selector(spec);
+
+        match threshold(spec, value) {
+          Ok(Some((status, expression))) => (status, format!("{selector} is {value}, matching `{expression}`")),
+          Ok(None) => (OK, format!("{selector} is {value}")),
+          Err(err) => (CRITICAL, format!("{err:#}")),
+        }
+      }
+
+      Err(err) => (CRITICAL, format!("{err:#}")),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Scrape the metrics endpoint, and return the value of the only series matching the metric name and labels.
+fn scrape(spec: &Prometheus) -> Result<f64> {
+  let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+  let request = Http {
+    url: spec.url.clone(),
+    timeout: Some(timeout),
+    ..Default::default()
+  };
+
+  let response = http::request(&http::agent(&request, *timeout)?, &request)?;
+
+  if !(200..=299).contains(&response.code) {
+    bail!("status code was {}", response.code);
+  }
+
+  let mut values = response
+    .body
+    .lines()
+    .filter_map(series)
+    .filter(|sample| sample.name == spec.metric && spec.labels.iter().all(|(label, value)| sample.labels.iter().any(|(name, other)| name == label && other == value)));
+
+  match (values.next(), values.next()) {
+    (Some(sample), None) => Ok(sample.value),
+    (None, _) => Err(anyhow!("no series found for {}", selector(spec))),
+    (Some(_), Some(_)) => Err(anyhow!("{} matches more than one series", selector(spec))),
+  }
+}
+
+/// Parse a sample line from the text exposition format into its metric name, labels and value.
+fn series(line: &str) -> Option<Sample> {
+  let line = line.trim();
+
+  if line.is_empty() || line.starts_with('#') {
+    return None;
+  }
+
+  let end = line.find(|c: char| c == '{' || c.is_whitespace())?;
+  let (name, mut rest) = line.split_at(end);
+  let mut labels = Vec::new();
+
+  if let Some(mut inner) = rest.strip_prefix('{') {
+    loop {
+      inner = inner.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+
+      if let Some(after) = inner.strip_prefix('}') {
+        rest = after;
+        break;
+      }
+
+      let (label, after) = inner.split_once('=')?;
+      let mut chars = after.trim_start().strip_prefix('"')?.char_indices();
+      let mut value = String::new();
+
+      let end = loop {
+        match chars.next()? {
+          (index, '"') => break index,
+          (_, '\\') => match chars.next()? {
+            (_, 'n') => value.push('\n'),
+            (_, c) => value.push(c),
+          },
+          (_, c) => value.push(c),
+        }
+      };
+
+      labels.push((label.trim().to_string(), value));
+      inner = &after.trim_start()[end + 2..];
+    }
+  }
+
+  let value = rest.split_whitespace().next()?.parse().ok()?;
+
+  Some(Sample {
+    name: name.to_string(),
+    labels,
+    value,
+  })
+}
+
+/// Evaluate the critical then warning thresholds, returning the status and expression of the first one reached.
+fn threshold(spec: &Prometheus, value: f64) -> Result<Option<(u8, &str)>> {
+  for (status, expression) in [(CRITICAL, &spec.critical), (WARNING, &spec.warning)] {
+    let Some(expression) = expression else { continue };
+
+    let captures = THRESHOLD.captures(expression).ok_or_else(|| anyhow!("invalid threshold `{expression}`"))?;
+
+    if let Some(name) = captures.get(1) {
+      if name.as_str() != spec.metric {
+        bail!("threshold `{expression}` does not apply to {}", spec.metric);
+      }
+    }
+
+    let limit = captures[3].parse::<f64>().map_err(|_| anyhow!("invalid threshold `{expression}`"))?;
+
+    let reached = match &captures[2] {
+      ">" => value > limit,
+      ">=" => value >= limit,
+      "<" => value < limit,
+      "<=" => value <= limit,
+      "==" => value == limit,
+      _ => value != limit,
+    };
+
+    if reached {
+      return Ok(Some((status, expression.trim())));
+    }
+  }
+
+  Ok(None)
+}
+
+fn selector(spec: &Prometheus) -> String {
+  if spec.labels.is_empty() {
+    return spec.metric.clone();
+  }
+
+  let mut labels = spec.labels.iter().map(|(label, value)| format!("{label}={value:?}")).collect::<Vec<_>>();
+  labels.sort();
+
+  format!("{}{{{}}}", spec.metric, labels.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashMap, net::SocketAddr};
+
+  use anyhow::Result;
+  use sqlx::types::Json;
+
+  use super::{series, Handler, PrometheusHandler, Sample};
+  use crate::{
+    config::CONTROLLER_ID,
+    handlers::testing,
+    model::{specs::Prometheus, status::*, Check},
+    stash::Stash,
+  };
+
+  const METRICS: &str = r#"
+# HELP queue_depth Number of jobs waiting in a queue.
+# TYPE queue_depth gauge
+queue_depth{queue="emails",region="eu"} 1234
+queue_depth{queue="reports",region="eu"} 12 1700000000000
+up 1
+"#;
+
+  fn spec(addr: SocketAddr, queue: &str) -> Prometheus {
+    Prometheus {
+      url: format!("http://{addr}/metrics"),
+      metric: "queue_depth".to_string(),
+      labels: Json(HashMap::from([("queue".to_string(), queue.to_string())])),
+      warning: Some("queue_depth > 1000".to_string()),
+      critical: Some(">= 5000".to_string()),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn prometheus_series() {
+    assert_eq!(
+      series("up 1"),
+      Some(Sample {
+        name: "up".to_string(),
+        labels: vec![],
+        value: 1.0
+      })
+    );
+    assert_eq!(series("# TYPE up gauge"), None);
+    assert_eq!(
+      series(r#"http_requests_total{method="post",path="/a \"b\"\\c"} 1027 1395066363000"#),
+      Some(Sample {
+        name: "http_requests_total".to_string(),
+        labels: vec![("method".to_string(), "post".to_string()), ("path".to_string(), r#"/a "b"\c"#.to_string())],
+        value: 1027.0
+      })
+    );
+    assert!(series("temperature{} +Inf").is_some_and(|sample| sample.value.is_infinite()));
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_prometheus_ok() -> Result<()> {
+    let addr = testing::serve(|_| ("200 OK", METRICS)).await?;
+
+    let handler = PrometheusHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, "reports"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert_eq!(result.message, r#"queue_depth{queue="reports"} is 12"#);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_prometheus_warning() -> Result<()> {
+    let addr = testing::serve(|_| ("200 OK", METRICS)).await?;
+
+    let handler = PrometheusHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, "emails"), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, WARNING);
+    assert_eq!(result.message, r#"queue_depth{queue="emails"} is 1234, matching `queue_depth > 1000`"#);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_prometheus_selection() -> Result<()> {
+    let addr = testing::serve(|_| ("200 OK", METRICS)).await?;
+
+    let handler = PrometheusHandler { check: &Check::default() };
+
+    let result = handler.run(&spec(addr, "invoices"), CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(result.message, r#"no series found for queue_depth{queue="invoices"}"#);
+
+    let spec = Prometheus {
+      labels: Json(HashMap::new()),
+      ..spec(addr, "")
+    };
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(result.message, "queue_depth matches more than one series");
+
+    Ok(())
+  }
+}
@@ -1,17 +1,54 @@
-use std::{ffi::CString, fs, sync::Arc};
+use std::{
+  ffi::{c_long, CString},
+  fs,
+  sync::{
+    atomic::{AtomicBool, AtomicI64, Ordering},
+    Arc,
+  },
+};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use pyo3::{ffi::c_str, prelude::*};
+use pyo3::{exceptions::PyTimeoutError, ffi, prelude::*};
+use serde_json::Value;
 use sqlx::MySqlConnection;
+use tokio::time::timeout;
 
 use crate::{
   config::Config,
   handlers::Handler,
-  model::{specs::Python, status::*, Check, Event},
+  model::{specs::Python, status::*, Check, Duration, Event},
   stash::Stash,
 };
 
+/// Context passed to scripts, exposing the site they run on and the stash of the check.
+#[pyclass(name = "Context")]
+struct CheckContext {
+  #[pyo3(get)]
+  site: String,
+  check: Check,
+  stash: Stash,
+}
+
+#[pymethods]
+impl CheckContext {
+  fn get(&self, key: &str) -> Option<String> {
+    self.stash.blocking_retrieve(&self.check, key)
+  }
+
+  fn set(&self, key: &str, value: &str) {
+    self.stash.clone().blocking_stash(&self.check, key, value);
+  }
+}
+
+/// State shared between a script and the task waiting for it, so that the script can be stopped once it times out.
+#[derive(Default)]
+struct Execution {
+  /// Identifier of the thread running the script, or zero once it is done with it.
+  thread: AtomicI64,
+  cancelled: AtomicBool,
+}
+
 pub struct PythonHandler<'h> {
   pub check: &'h Check,
   pub path: String,
@@ -27,20 +64,35 @@ impl Handler for PythonHandler<'_> {
     self.run(&spec, site, stash).await
   }
 
-  async fn run(&self, spec: &Python, site: &str, _stash: Stash) -> Result<Event> {
+  async fn run(&self, spec: &Python, site: &str, stash: Stash) -> Result<Event> {
     let file = format!("{}/{}.py", self.path, spec.script);
     let code = fs::read_to_string(&file)?;
 
-    let (status, message): (u8, String) = pyo3::Python::with_gil(|py| {
-      let module = PyModule::from_code(py, CString::new(code)?.as_c_str(), c_str!("check.py"), c_str!("check"))?;
-      module.setattr("OK", OK)?;
-      module.setattr("WARNING", WARNING)?;
-      module.setattr("CRITICAL", CRITICAL)?;
+    let context = CheckContext {
+      site: site.to_string(),
+      check: self.check.clone(),
+      stash,
+    };
+
+    let execution = Arc::new(Execution::default());
+
+    // Scripts hold the GIL while they run, so they are evaluated on a blocking thread.
+    let task = tokio::task::spawn_blocking({
+      let spec = spec.clone();
+      let execution = execution.clone();
 
-      let (status, message) = module.getattr("check")?.call0()?.extract()?;
+      move || evaluate(&spec, code, context, &execution)
+    });
 
-      PyResult::Ok((status, message))
-    })?;
+    let (status, message) = match timeout(*spec.timeout.unwrap_or_else(|| Duration::from(5)), task).await {
+      Ok(result) => result??,
+
+      Err(_) => {
+        tokio::task::spawn_blocking(move || interrupt(&execution));
+
+        (CRITICAL, "script timed out".to_string())
+      }
+    };
 
     let event = Event {
       check_id: self.check.id,
@@ -54,12 +106,87 @@ impl Handler for PythonHandler<'_> {
   }
 }
 
+/// Run the script on the current thread, recording it so that the script can be interrupted.
+fn evaluate(spec: &Python, code: String, context: CheckContext, execution: &Execution) -> PyResult<(u8, String)> {
+  pyo3::Python::with_gil(|py| {
+    let thread = py.import("threading")?.call_method0("get_ident")?.extract()?;
+    execution.thread.store(thread, Ordering::SeqCst);
+
+    let result = call(py, spec, code, context, execution);
+
+    // The thread returns to the blocking pool, so it must not be interrupted anymore, and an interruption which was
+    // requested but not raised yet is discarded.
+    execution.thread.swap(0, Ordering::SeqCst);
+
+    if execution.cancelled.load(Ordering::SeqCst) {
+      unsafe {
+        ffi::PyThreadState_SetAsyncExc(thread as c_long, std::ptr::null_mut());
+      }
+    }
+
+    result
+  })
+}
+
+/// Load the script as a module named after it, and call its `check()` function with as many of the parameters and context as it accepts.
+fn call(py: pyo3::Python<'_>, spec: &Python, code: String, context: CheckContext, execution: &Execution) -> PyResult<(u8, String)> {
+  // The script may have timed out before the GIL could be acquired.
+  if execution.cancelled.load(Ordering::SeqCst) {
+    return Err(PyTimeoutError::new_err("script timed out"));
+  }
+
+  let file = CString::new(format!("{}.py", spec.script))?;
+  let name = CString::new(spec.script.as_str())?;
+
+  let module = PyModule::from_code(py, CString::new(code)?.as_c_str(), file.as_c_str(), name.as_c_str())?;
+  module.setattr("OK", OK)?;
+  module.setattr("WARNING", WARNING)?;
+  module.setattr("CRITICAL", CRITICAL)?;
+  module.setattr("UNKNOWN", UNKNOWN)?;
+
+  let check = module.getattr("check")?;
+  let params = py.import("json")?.call_method1("loads", (Value::Object(spec.params.0.clone()).to_string(),))?;
+  let arity = py.import("inspect")?.call_method1("signature", (&check,))?.getattr("parameters")?.len()?;
+
+  // The script may have timed out while it was being loaded.
+  if execution.cancelled.load(Ordering::SeqCst) {
+    return Err(PyTimeoutError::new_err("script timed out"));
+  }
+
+  let result = match arity {
+    0 => check.call0(),
+    1 => check.call1((params,)),
+    _ => check.call1((params, context)),
+  };
+
+  result?.extract()
+}
+
+/// Raise a `TimeoutError` in the thread running a script, stopping it at its next instruction, or keep the script from
+/// being called if it has not started yet.
+fn interrupt(execution: &Execution) {
+  pyo3::Python::with_gil(|_| {
+    execution.cancelled.store(true, Ordering::SeqCst);
+
+    let thread = execution.thread.swap(0, Ordering::SeqCst);
+
+    if thread != 0 {
+      unsafe {
+        ffi::PyThreadState_SetAsyncExc(thread as c_long, ffi::PyExc_TimeoutError);
+      }
+    }
+  });
+}
+
 #[cfg(test)]
 mod tests {
+  use serde_json::json;
+  use sqlx::types::Json;
+
   use super::{Handler, PythonHandler};
   use crate::{
     config::CONTROLLER_ID,
-    model::{specs::Python, status::*, Check},
+    model::{specs::Python, status::*, Check, Duration},
     stash::Stash,
   };
 
@@ -73,6 +200,20 @@ def check():
   return (CRITICAL, "this is the CRITICAL check message")
   "#;
 
+  const SCRIPT_CONTEXT: &str = r#"
+def check(params, ctx):
+  runs = int(ctx.get("runs") or 0) + 1
+  ctx.set("runs", str(runs))
+
+  return (OK if runs < params["limit"] else WARNING, f"run {runs} on {ctx.site}")
+  "#;
+
+  const SCRIPT_TIMEOUT: &str = r#"
+def check(params):
+  while True:
+    pass
+  "#;
+
   const SCRIPT_SYNTAX_ERROR: &str = r#"
 def check():
 invalid
@@ -93,9 +234,8 @@ def check():
     };
 
     let spec = Python {
-      id: 0,
-      check_id: 0,
       script: "ok".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -116,9 +256,8 @@ def check():
     };
 
     let spec = Python {
-      id: 0,
-      check_id: 0,
       script: "critical".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -129,6 +268,61 @@ def check():
     assert_eq!(&result.message, "this is the CRITICAL check message");
   }
 
+  #[tokio::test]
+  async fn handler_python_context() {
+    write_script("context", SCRIPT_CONTEXT);
+
+    let handler = PythonHandler {
+      check: &Check::default(),
+      path: "/tmp".to_string(),
+    };
+
+    let spec = Python {
+      script: "context".to_string(),
+      params: Json(json!({ "limit": 2 }).as_object().cloned().unwrap_or_default()),
+      ..Default::default()
+    };
+
+    let stash = Stash::new();
+
+    let result = handler.run(&spec, CONTROLLER_ID, stash.clone()).await.unwrap();
+    assert_eq!(result.status, OK);
+    assert_eq!(&result.message, "run 1 on @controller");
+
+    let result = handler.run(&spec, CONTROLLER_ID, stash.clone()).await.unwrap();
+    assert_eq!(result.status, WARNING);
+    assert_eq!(&result.message, "run 2 on @controller");
+  }
+
+  #[tokio::test]
+  async fn handler_python_timeout() {
+    write_script("timeout", SCRIPT_TIMEOUT);
+    write_script("aftertimeout", SCRIPT_OK);
+
+    let handler = PythonHandler {
+      check: &Check::default(),
+      path: "/tmp".to_string(),
+    };
+
+    let spec = Python {
+      script: "timeout".to_string(),
+      timeout: Some(Duration::from(1)),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await.unwrap();
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "script timed out");
+
+    let spec = Python {
+      script: "aftertimeout".to_string(),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await.unwrap();
+    assert_eq!(result.status, OK);
+  }
+
   #[tokio::test]
   async fn handler_python_missing() {
     let handler = PythonHandler {
@@ -137,9 +331,8 @@ def check():
     };
 
     let spec = Python {
-      id: 0,
-      check_id: 0,
       script: "missing".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -160,9 +353,8 @@ def check():
     };
 
     let spec = Python {
-      id: 0,
-      check_id: 0,
       script: "syntaxerror".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -183,9 +375,8 @@ def check():
     };
 
     let spec = Python {
-      id: 0,
-      check_id: 0,
       script: "nocheck".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -206,9 +397,8 @@ def check():
     };
 
     let spec = Python {
-      id: 0,
-      check_id: 0,
       script: "wrongtype".to_string(),
+      ..Default::default()
     };
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
@@ -0,0 +1,306 @@
+use std::{
+  net::{SocketAddr, TcpStream, ToSocketAddrs},
+  sync::{mpsc, Arc},
+  thread,
+  time::Instant,
+};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use rhai::{
+  module_resolvers::DummyModuleResolver,
+  packages::{
+    ArithmeticPackage, BasicArrayPackage, BasicFnPackage, BasicIteratorPackage, BasicMapPackage, BasicMathPackage, BasicStringPackage, BitFieldPackage, LogicPackage, MoreStringPackage, Package,
+  },
+  Array, Dynamic, Engine, EvalAltResult, Map, Scope,
+};
+use sqlx::MySqlConnection;
+
+use crate::{
+  config::Config,
+  handlers::{http, Handler},
+  model::{
+    specs::{Http, Script},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+/// Maximum number of operations a script can perform, to stop runaway loops before they hit the timeout.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+pub struct ScriptHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for ScriptHandler<'_> {
+  type Spec = Script;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Script::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Script, site: &str, stash: Stash) -> Result<Event> {
+    let check = self.check.clone();
+    let timeout = *spec.timeout.unwrap_or_else(|| Duration::from(5));
+    let spec = spec.clone();
+
+    // Helpers perform blocking I/O, and the stash can only be accessed synchronously outside of the runtime. The engine only
+    // checks the timeout between operations, so the whole evaluation is bounded as well in case a helper call hangs.
+    let (status, message) = match tokio::time::timeout(timeout, tokio::task::spawn_blocking(move || evaluate(&check, &spec, stash))).await {
+      Ok(result) => match result? {
+        Ok(result) => result,
+        Err(err) => (CRITICAL, format!("{err:#}")),
+      },
+      Err(_) => (CRITICAL, "script timed out".to_string()),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Evaluate the script in a sandboxed engine, and return the status and message it evaluates to.
+fn evaluate(check: &Check, spec: &Script, stash: Stash) -> Result<(u8, String)> {
+  let timeout = *spec.timeout.unwrap_or_else(|| Duration::from(5));
+  let engine = engine(check, stash, timeout);
+
+  let mut scope = Scope::new();
+  scope.push_constant("OK", OK as i64);
+  scope.push_constant("WARNING", WARNING as i64);
+  scope.push_constant("CRITICAL", CRITICAL as i64);
+  scope.push_constant("UNKNOWN", UNKNOWN as i64);
+
+  let result = engine.eval_with_scope::<Dynamic>(&mut scope, &spec.code).map_err(|err| match *err {
+    EvalAltResult::ErrorTerminated(..) => anyhow!("script timed out"),
+    err => anyhow!("script failed: {err}"),
+  })?;
+
+  let result = result
+    .try_cast::<Array>()
+    .filter(|result| result.len() == 2)
+    .ok_or_else(|| anyhow!("script must evaluate to [status, message]"))?;
+
+  let status = result[0]
+    .as_int()
+    .ok()
+    .and_then(|status| u8::try_from(status).ok())
+    .filter(|status| [OK, WARNING, CRITICAL, UNKNOWN].contains(status))
+    .ok_or_else(|| anyhow!("script returned an invalid status"))?;
+
+  Ok((status, result[1].to_string()))
+}
+
+/// Build an engine without access to the filesystem, limited in operations and time, and exposing network and stash helpers.
+fn engine(check: &Check, stash: Stash, timeout: std::time::Duration) -> Engine {
+  let mut engine = Engine::new_raw();
+  let start = Instant::now();
+
+  // The standard package minus the core language one, which provides `sleep`, and the time one.
+  engine.register_global_module(ArithmeticPackage::new().as_shared_module());
+  engine.register_global_module(LogicPackage::new().as_shared_module());
+  engine.register_global_module(BitFieldPackage::new().as_shared_module());
+  engine.register_global_module(BasicMathPackage::new().as_shared_module());
+  engine.register_global_module(BasicStringPackage::new().as_shared_module());
+  engine.register_global_module(MoreStringPackage::new().as_shared_module());
+  engine.register_global_module(BasicIteratorPackage::new().as_shared_module());
+  engine.register_global_module(BasicArrayPackage::new().as_shared_module());
+  engine.register_global_module(BasicMapPackage::new().as_shared_module());
+  engine.register_global_module(BasicFnPackage::new().as_shared_module());
+
+  engine.set_module_resolver(DummyModuleResolver::new());
+  engine.disable_symbol("eval");
+  engine.set_max_operations(MAX_OPERATIONS);
+  engine.set_max_call_levels(32);
+  engine.set_max_string_size(1 << 20);
+  engine.set_max_array_size(10_000);
+  engine.set_max_map_size(10_000);
+  engine.on_progress(move |_| (start.elapsed() > timeout).then_some(Dynamic::UNIT));
+
+  engine.register_fn("http_get", move |url: &str| -> Result<Map, Box<EvalAltResult>> {
+    let request = Http {
+      url: url.to_string(),
+      ..Default::default()
+    };
+
+    let response = http::agent(&request, timeout).and_then(|agent| http::request(&agent, &request)).map_err(|err| format!("{err:#}"))?;
+
+    let mut result = Map::new();
+    result.insert("status".into(), Dynamic::from(response.code as i64));
+    result.insert("body".into(), Dynamic::from(response.body));
+
+    Ok(result)
+  });
+
+  engine.register_fn("tcp_connect", move |host: &str, port: i64| -> bool {
+    let Ok(port) = u16::try_from(port) else { return false };
+
+    resolve(host, port, timeout).iter().any(|addr| TcpStream::connect_timeout(addr, timeout).is_ok())
+  });
+
+  engine.register_fn("dns_lookup", move |name: &str| -> Array {
+    let mut addrs = resolve(name, 0, timeout).into_iter().map(|addr| addr.ip().to_string()).collect::<Vec<_>>();
+    addrs.dedup();
+
+    addrs.into_iter().map(Dynamic::from).collect()
+  });
+
+  engine.register_fn("stash_get", {
+    let check = check.clone();
+    let stash = stash.clone();
+
+    move |key: &str| -> Dynamic { stash.blocking_retrieve(&check, key).map(Dynamic::from).unwrap_or(Dynamic::UNIT) }
+  });
+
+  engine.register_fn("stash_set", {
+    let check = check.clone();
+
+    move |key: &str, value: Dynamic| stash.clone().blocking_stash(&check, key, &value.to_string())
+  });
+
+  engine
+}
+
+/// Resolve a host with the system resolver, giving up once the timeout is reached. Lookups cannot be cancelled, so they run
+/// on their own thread which is left behind when it takes too long.
+pub(crate) fn resolve(host: &str, port: u16, timeout: std::time::Duration) -> Vec<SocketAddr> {
+  let (tx, rx) = mpsc::channel();
+  let host = host.to_string();
+
+  thread::spawn(move || {
+    let _ = tx.send((host.as_str(), port).to_socket_addrs().map(Iterator::collect::<Vec<_>>));
+  });
+
+  rx.recv_timeout(timeout).ok().and_then(Result::ok).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use anyhow::Result;
+  use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+  use super::{Handler, ScriptHandler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::Script, status::*, Check, Duration},
+    stash::Stash,
+  };
+
+  fn spec(code: &str) -> Script {
+    Script {
+      code: code.to_string(),
+      timeout: Some(Duration::from(1)),
+      ..Default::default()
+    }
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_script_ok() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      while let Ok((mut stream, _)) = listener.accept().await {
+        let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 4\r\nconnection: close\r\n\r\npong").await;
+      }
+    });
+
+    let code = format!(
+      r#"
+        let response = http_get("http://{addr}/ping");
+
+        if !tcp_connect("127.0.0.1", {port}) {{
+          return [CRITICAL, "port is closed"];
+        }}
+
+        if response.status != 200 {{
+          return [CRITICAL, `status code was ${{response.status}}`];
+        }}
+
+        [OK, response.body]
+      "#,
+      port = addr.port()
+    );
+
+    let handler = ScriptHandler { check: &Check::default() };
+    let result = handler.run(&spec(&code), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert_eq!(result.message, "pong");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_script_stash() -> Result<()> {
+    let code = r#"
+      let runs = stash_get("runs");
+      let runs = if runs == () { 1 } else { parse_int(runs) + 1 };
+      stash_set("runs", runs);
+
+      [if runs > 1 { WARNING } else { OK }, `run ${runs}`]
+    "#;
+
+    let handler = ScriptHandler { check: &Check::default() };
+    let stash = Stash::new();
+
+    let result = handler.run(&spec(code), CONTROLLER_ID, stash.clone()).await?;
+    assert_eq!((result.status, result.message.as_str()), (OK, "run 1"));
+
+    let result = handler.run(&spec(code), CONTROLLER_ID, stash.clone()).await?;
+    assert_eq!((result.status, result.message.as_str()), (WARNING, "run 2"));
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_script_errors() -> Result<()> {
+    let handler = ScriptHandler { check: &Check::default() };
+
+    // Accept connections but never answer, so each request blocks until the timeout.
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let mut streams = Vec::new();
+
+      while let Ok((stream, _)) = listener.accept().await {
+        streams.push(stream);
+      }
+    });
+
+    let result = handler.run(&spec("[OK, "), CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("script failed: "));
+
+    let result = handler.run(&spec("sleep(10); [OK, \"\"]"), CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("script failed: Function not found: sleep"));
+
+    let code = format!(r#"loop {{ try {{ http_get("http://{addr}/"); }} catch {{}} }}"#);
+    let result = handler.run(&spec(&code), CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!((result.status, result.message.as_str()), (CRITICAL, "script timed out"));
+
+    let result = handler.run(&spec("let x = 0; loop { x += 1; }"), CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!(result.status, CRITICAL);
+
+    let result = handler.run(&spec("[42, \"lorem\"]"), CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!((result.status, result.message.as_str()), (CRITICAL, "script returned an invalid status"));
+
+    let result = handler.run(&spec(r#"import "secrets" as secrets; [OK, ""]"#), CONTROLLER_ID, Stash::new()).await?;
+    assert_eq!(result.status, CRITICAL);
+
+    Ok(())
+  }
+}
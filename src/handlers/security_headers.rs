@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::MySqlConnection;
+use url::Url;
+
+use crate::{
+  config::Config,
+  handlers::{
+    http::{self, Response},
+    Handler,
+  },
+  model::{
+    specs::{Http, SecurityHeaders},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+/// HSTS max-age required when none is configured, as expected for preloading.
+const DEFAULT_HSTS_MAX_AGE: u64 = 31_536_000;
+
+pub struct SecurityHeadersHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for SecurityHeadersHandler<'_> {
+  type Spec = SecurityHeaders;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = SecurityHeaders::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &SecurityHeaders, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+    let request = Http {
+      url: spec.url.clone(),
+      max_hops: Some(10),
+      timeout: Some(timeout),
+      ..Default::default()
+    };
+
+    let (status, message) = match http::agent(&request, *timeout).and_then(|agent| http::request(&agent, &request)) {
+      Ok(response) => {
+        let mut findings = audit(spec, &response);
+
+        if let Some(finding) = https_redirect(spec, &response, *timeout) {
+          findings.push(("https_redirect", finding));
+        }
+
+        let grade = match findings.len() {
+          0 => 'A',
+          1 => 'B',
+          2 => 'C',
+          3 => 'D',
+          _ => 'F',
+        };
+
+        let status = match findings.iter().any(|(audit, _)| spec.mandatory.iter().any(|mandatory| mandatory == audit)) {
+          true => CRITICAL,
+          false if !findings.is_empty() => WARNING,
+          false => OK,
+        };
+
+        match findings.is_empty() {
+          true => (status, format!("grade {grade}")),
+          false => (status, format!("grade {grade}: {}", findings.into_iter().map(|(_, finding)| finding).collect::<Vec<_>>().join("; "))),
+        }
+      }
+
+      Err(err) => (CRITICAL, err.to_string()),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Audit the headers of the final response, and return each finding along with the audit which produced it.
+fn audit(spec: &SecurityHeaders, response: &Response) -> Vec<(&'static str, String)> {
+  let mut findings = Vec::new();
+  let header = |name: &str| response.headers.get(name).map(|value| value.trim().to_lowercase());
+
+  let minimum = spec.hsts_max_age.map(|age| age.as_secs()).unwrap_or(DEFAULT_HSTS_MAX_AGE);
+
+  match header("strict-transport-security") {
+    Some(hsts) => {
+      let age = hsts
+        .split(';')
+        .find_map(|directive| directive.trim().strip_prefix("max-age="))
+        .and_then(|age| age.trim_matches('"').parse::<u64>().ok())
+        .unwrap_or_default();
+
+      if age < minimum {
+        findings.push(("hsts", format!("HSTS max-age is {age}, expected at least {minimum}")));
+      }
+    }
+
+    None => findings.push(("hsts", "HSTS header is missing".to_string())),
+  }
+
+  let csp = header("content-security-policy");
+
+  if csp.is_none() {
+    findings.push(("csp", "CSP header is missing".to_string()));
+  }
+
+  match header("x-content-type-options") {
+    Some(options) if options == "nosniff" => {}
+    Some(options) => findings.push(("content_type_options", format!("X-Content-Type-Options is {options}, expected nosniff"))),
+    None => findings.push(("content_type_options", "X-Content-Type-Options header is missing".to_string())),
+  }
+
+  let frame_options = header("x-frame-options").is_some_and(|options| options == "deny" || options == "sameorigin");
+  let frame_ancestors = csp.is_some_and(|csp| csp.split(';').any(|directive| directive.trim().starts_with("frame-ancestors")));
+
+  if !frame_options && !frame_ancestors {
+    findings.push(("frame_options", "frame protection is missing".to_string()));
+  }
+
+  for cookie in &response.cookies {
+    let mut attributes = cookie.split(';').map(|attribute| attribute.trim().to_lowercase());
+    let name = attributes.next().and_then(|pair| pair.split_once('=').map(|(name, _)| name.to_string())).unwrap_or_default();
+    let attributes = attributes.collect::<Vec<_>>();
+
+    let missing = [("Secure", "secure"), ("HttpOnly", "httponly"), ("SameSite", "samesite")]
+      .into_iter()
+      .filter(|(_, flag)| !attributes.iter().any(|attribute| attribute == flag || attribute.starts_with(&format!("{flag}="))))
+      .map(|(flag, _)| flag)
+      .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+      findings.push(("cookies", format!("cookie `{name}` is missing {}", missing.join(", "))));
+    }
+  }
+
+  findings
+}
+
+/// Verify that plaintext requests are redirected to https, and that no redirect downgrades to http.
+fn https_redirect(spec: &SecurityHeaders, response: &Response, timeout: std::time::Duration) -> Option<String> {
+  if let Some(hop) = response.hops.windows(2).find(|hop| hop[0].starts_with("https://") && hop[1].starts_with("http://")) {
+    return Some(format!("redirect from {} to {} downgrades to http", hop[0], hop[1]));
+  }
+
+  let (url, location) = match spec.url.starts_with("http://") {
+    true => (spec.url.clone(), response.hops.get(1).cloned()),
+
+    false => {
+      // Plaintext visitors reach the default port, so that is the one probed even when the URL has another port.
+      let mut url = Url::parse(&spec.url).ok()?;
+      url.set_scheme("http").ok()?;
+      url.set_port(None).ok()?;
+
+      let request = Http {
+        url: url.to_string(),
+        follow_redirects: false,
+        ..Default::default()
+      };
+
+      // A server which does not listen for plaintext requests cannot serve them either.
+      let response = http::request(&http::agent(&request, timeout).ok()?, &request).ok()?;
+      let location = response.headers.get("location").and_then(|location| url.join(location).ok()).map(String::from);
+
+      (request.url, location)
+    }
+  };
+
+  match location {
+    Some(location) if location.starts_with("https://") => None,
+    _ => Some(format!("{url} does not redirect to https")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anyhow::Result;
+
+  use super::{Handler, SecurityHeadersHandler};
+  use crate::{
+    config::CONTROLLER_ID,
+    handlers::testing,
+    model::{specs::SecurityHeaders, status::*, Check, Duration},
+    stash::Stash,
+  };
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_security_headers_warning() -> Result<()> {
+    // The server never redirects to https.
+    let head = concat!(
+      "200 OK\r\n",
+      "strict-transport-security: max-age=63072000; includeSubDomains\r\n",
+      "content-security-policy: default-src 'self'; frame-ancestors 'none'\r\n",
+      "x-content-type-options: nosniff\r\n",
+      "set-cookie: session=42; Secure; HttpOnly; SameSite=Strict",
+    );
+
+    let addr = testing::serve(move |_| (head, "")).await?;
+
+    let handler = SecurityHeadersHandler { check: &Check::default() };
+    let spec = SecurityHeaders {
+      url: format!("http://{addr}/"),
+      mandatory: vec!["hsts".to_string(), "cookies".to_string()].into(),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, WARNING);
+    assert_eq!(result.message, format!("grade B: http://{addr}/ does not redirect to https"));
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_security_headers_critical() -> Result<()> {
+    let head = concat!(
+      "200 OK\r\n",
+      "strict-transport-security: max-age=3600\r\n",
+      "x-frame-options: SAMEORIGIN\r\n",
+      "set-cookie: session=42; HttpOnly"
+    );
+    let addr = testing::serve(move |_| (head, "")).await?;
+
+    let handler = SecurityHeadersHandler { check: &Check::default() };
+    let spec = SecurityHeaders {
+      url: format!("http://{addr}/"),
+      hsts_max_age: Some(Duration::from(86400)),
+      mandatory: vec!["cookies".to_string()].into(),
+      ..Default::default()
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(
+      result.message,
+      format!(
+        "grade F: HSTS max-age is 3600, expected at least 86400; CSP header is missing; X-Content-Type-Options header is missing; cookie `session` is missing Secure, SameSite; http://{addr}/ does not redirect to https"
+      )
+    );
+
+    Ok(())
+  }
+}
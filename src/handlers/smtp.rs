@@ -0,0 +1,320 @@
+use std::{
+  net::{SocketAddr, ToSocketAddrs},
+  sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::MySqlConnection;
+use tokio::{
+  io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+  net::TcpStream,
+  time,
+};
+
+use crate::{
+  config::Config,
+  handlers::{starttls, Handler},
+  model::{specs::Smtp, status::*, Check, Duration, Event},
+  stash::Stash,
+};
+
+pub struct SmtpHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for SmtpHandler<'_> {
+  type Spec = Smtp;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Smtp::for_check(conn, self.check).await.context("no spec found")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Smtp, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+
+    let addr = format!("{}:{}", spec.host, spec.port);
+    let addr = addr.to_socket_addrs().context("could not parse host")?.next().ok_or_else(|| anyhow!("could not parse host"))?;
+
+    let (status, message) = match time::timeout(*timeout, session(spec, addr)).await {
+      Ok(Ok(())) => (OK, String::new()),
+      Ok(Err(err)) => (CRITICAL, format!("{err:#}")),
+      Err(err) => (CRITICAL, err.to_string()),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+async fn session(spec: &Smtp, addr: SocketAddr) -> Result<()> {
+  let mut stream = BufReader::new(TcpStream::connect(addr).await?);
+
+  match reply(&mut stream).await? {
+    (220, _) => (),
+    (code, lines) => return Err(anyhow!("unexpected banner: {} {}", code, lines.join(" "))),
+  }
+
+  let extensions = ehlo(&mut stream).await?;
+
+  if !spec.starttls {
+    verify(spec, &extensions)?;
+
+    return quit(&mut stream).await;
+  }
+
+  if !extensions.iter().any(|(keyword, _)| keyword == "STARTTLS") {
+    return Err(anyhow!("STARTTLS is not advertised"));
+  }
+
+  match command(&mut stream, "STARTTLS").await? {
+    (220, _) => (),
+    (code, lines) => return Err(anyhow!("STARTTLS was refused: {} {}", code, lines.join(" "))),
+  }
+
+  let mut stream = BufReader::new(starttls::upgrade(stream.into_inner(), &spec.host).await?);
+  let extensions = ehlo(&mut stream).await?;
+
+  verify(spec, &extensions)?;
+
+  quit(&mut stream).await
+}
+
+fn verify(spec: &Smtp, extensions: &[(String, Vec<String>)]) -> Result<()> {
+  for extension in spec.extensions.iter() {
+    if !extensions.iter().any(|(keyword, _)| keyword.eq_ignore_ascii_case(extension)) {
+      return Err(anyhow!("extension {} is not advertised", extension.to_uppercase()));
+    }
+  }
+
+  if !spec.auth_mechanisms.is_empty() {
+    let mechanisms = extensions.iter().find(|(keyword, _)| keyword == "AUTH").map(|(_, params)| params.as_slice()).unwrap_or_default();
+
+    for mechanism in spec.auth_mechanisms.iter() {
+      if !mechanisms.iter().any(|param| param.eq_ignore_ascii_case(mechanism)) {
+        return Err(anyhow!("authentication mechanism {} is not advertised", mechanism.to_uppercase()));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+async fn ehlo<S>(stream: &mut S) -> Result<Vec<(String, Vec<String>)>>
+where
+  S: AsyncBufRead + AsyncWrite + Unpin,
+{
+  let lines = match command(stream, "EHLO defcon").await? {
+    (250, lines) => lines,
+    (code, lines) => return Err(anyhow!("EHLO was refused: {} {}", code, lines.join(" "))),
+  };
+
+  let extensions = lines
+    .iter()
+    .skip(1)
+    .filter_map(|line| {
+      let mut tokens = line.split_whitespace();
+      let keyword = tokens.next()?.to_uppercase();
+
+      Some((keyword, tokens.map(ToOwned::to_owned).collect()))
+    })
+    .collect();
+
+  Ok(extensions)
+}
+
+async fn quit<S>(stream: &mut S) -> Result<()>
+where
+  S: AsyncBufRead + AsyncWrite + Unpin,
+{
+  command(stream, "QUIT").await?;
+
+  Ok(())
+}
+
+async fn command<S>(stream: &mut S, command: &str) -> Result<(u16, Vec<String>)>
+where
+  S: AsyncBufRead + AsyncWrite + Unpin,
+{
+  stream.write_all(format!("{command}\r\n").as_bytes()).await?;
+  stream.flush().await?;
+
+  reply(stream).await
+}
+
+async fn reply<S>(stream: &mut S) -> Result<(u16, Vec<String>)>
+where
+  S: AsyncBufRead + Unpin,
+{
+  let mut lines = Vec::new();
+
+  loop {
+    let mut line = String::new();
+
+    if stream.read_line(&mut line).await? == 0 {
+      return Err(anyhow!("connection closed by server"));
+    }
+
+    let line = line.trim_end();
+    let code = line.get(..3).and_then(|code| code.parse::<u16>().ok()).ok_or_else(|| anyhow!("invalid SMTP reply: {}", line))?;
+
+    lines.push(line.get(4..).unwrap_or_default().to_string());
+
+    if line.as_bytes().get(3) != Some(&b'-') {
+      return Ok((code, lines));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::SocketAddr;
+
+  use anyhow::Result;
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+  };
+
+  use super::{Handler, SmtpHandler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::Smtp, status::*, Check},
+    stash::Stash,
+  };
+
+  async fn server(banner: &'static str, extensions: &'static [&'static str]) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let mut stream = BufReader::new(stream);
+
+      stream.write_all(format!("{banner}\r\n").as_bytes()).await.unwrap();
+
+      loop {
+        let mut line = String::new();
+
+        if stream.read_line(&mut line).await.unwrap() == 0 {
+          break;
+        }
+
+        match line.trim_end() {
+          "EHLO defcon" => {
+            let separator = if extensions.is_empty() { ' ' } else { '-' };
+            let mut response = format!("250{separator}smtp.example.com\r\n");
+
+            for (index, extension) in extensions.iter().enumerate() {
+              let separator = if index == extensions.len() - 1 { ' ' } else { '-' };
+
+              response.push_str(&format!("250{separator}{extension}\r\n"));
+            }
+
+            stream.write_all(response.as_bytes()).await.unwrap();
+          }
+
+          "QUIT" => {
+            stream.write_all(b"221 Bye\r\n").await.unwrap();
+            break;
+          }
+
+          _ => stream.write_all(b"502 Command not implemented\r\n").await.unwrap(),
+        }
+      }
+    });
+
+    Ok(addr)
+  }
+
+  fn spec(addr: SocketAddr, extensions: &[&str], auth_mechanisms: &[&str]) -> Smtp {
+    Smtp {
+      id: 0,
+      check_id: 0,
+      host: addr.ip().to_string(),
+      port: addr.port(),
+      starttls: false,
+      extensions: extensions.iter().map(ToString::to_string).collect::<Vec<_>>().into(),
+      auth_mechanisms: auth_mechanisms.iter().map(ToString::to_string).collect::<Vec<_>>().into(),
+      timeout: Some(1.into()),
+    }
+  }
+
+  #[tokio::test]
+  async fn handler_smtp_ok() -> Result<()> {
+    let addr = server("220 smtp.example.com ESMTP", &["SIZE 10240000", "STARTTLS", "AUTH PLAIN LOGIN"]).await?;
+
+    let handler = SmtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, &["size", "STARTTLS"], &["PLAIN"]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_smtp_invalid_banner() -> Result<()> {
+    let addr = server("421 Service not available", &[]).await?;
+
+    let handler = SmtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, &[], &[]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "unexpected banner: 421 Service not available");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_smtp_missing_extension() -> Result<()> {
+    let addr = server("220 smtp.example.com ESMTP", &["SIZE 10240000"]).await?;
+
+    let handler = SmtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, &["STARTTLS"], &[]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "extension STARTTLS is not advertised");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_smtp_missing_auth_mechanism() -> Result<()> {
+    let addr = server("220 smtp.example.com ESMTP", &["AUTH PLAIN"]).await?;
+
+    let handler = SmtpHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, &[], &["CRAM-MD5"]), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "authentication mechanism CRAM-MD5 is not advertised");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_smtp_starttls_not_advertised() -> Result<()> {
+    let addr = server("220 smtp.example.com ESMTP", &["SIZE 10240000"]).await?;
+
+    let handler = SmtpHandler { check: &Check::default() };
+    let spec = Smtp {
+      starttls: true,
+      ..spec(addr, &[], &[])
+    };
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "STARTTLS is not advertised");
+
+    Ok(())
+  }
+}
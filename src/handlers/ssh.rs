@@ -0,0 +1,186 @@
+use std::{
+  net::{SocketAddr, TcpStream, ToSocketAddrs},
+  sync::Arc,
+  time::Duration as StdDuration,
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD_NO_PAD as b64, Engine as _};
+use sqlx::MySqlConnection;
+use ssh2::{HashType, MethodType, Session};
+
+use crate::{
+  config::Config,
+  handlers::Handler,
+  model::{specs::Ssh, status::*, Check, Duration, Event},
+  stash::Stash,
+};
+
+pub struct SshHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for SshHandler<'_> {
+  type Spec = Ssh;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Ssh::for_check(conn, self.check).await.context("no spec found")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Ssh, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+
+    let addr = format!("{}:{}", spec.host, spec.port);
+    let addr = addr.to_socket_addrs().context("could not parse host")?.next().ok_or_else(|| anyhow!("could not parse host"))?;
+
+    let (status, message) = match handshake(spec, addr, *timeout) {
+      Ok((banner, fingerprint)) => match spec.fingerprint {
+        Some(ref expected) if expected.trim_start_matches("SHA256:").trim_end_matches('=') != fingerprint.trim_start_matches("SHA256:") => {
+          (CRITICAL, format!("host key fingerprint mismatch: expected {expected}, got {fingerprint} ({banner})"))
+        }
+
+        _ => (OK, banner),
+      },
+
+      Err(err) => (CRITICAL, format!("{err:#}")),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+fn handshake(spec: &Ssh, addr: SocketAddr, timeout: StdDuration) -> Result<(String, String)> {
+  let stream = TcpStream::connect_timeout(&addr, timeout)?;
+  let mut session = Session::new().context("could not create SSH session")?;
+
+  session.set_timeout(timeout.as_millis() as u32);
+  session.set_tcp_stream(stream);
+
+  if let Some(ref algorithm) = spec.algorithm {
+    session.method_pref(MethodType::HostKey, algorithm).context("invalid host key algorithm")?;
+  }
+
+  session.handshake().context("SSH handshake failed")?;
+
+  let banner = session.banner().unwrap_or_default().to_string();
+  let hash = session.host_key_hash(HashType::Sha256).ok_or_else(|| anyhow!("could not retrieve host key"))?;
+  let fingerprint = format!("SHA256:{}", b64.encode(hash));
+
+  let _ = session.disconnect(None, "", None);
+
+  Ok((banner, fingerprint))
+}
+
+#[cfg(test)]
+mod tests {
+  use anyhow::Result;
+  use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+  use super::{Handler, SshHandler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::Ssh, status::*, Check},
+    stash::Stash,
+  };
+
+  #[tokio::test]
+  async fn handler_ssh_ok() -> Result<()> {
+    let handler = SshHandler { check: &Check::default() };
+    let spec = Ssh {
+      id: 0,
+      check_id: 0,
+      host: "github.com".to_string(),
+      port: 22,
+      algorithm: Some("ssh-ed25519".to_string()),
+      fingerprint: Some("SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU".to_string()),
+      timeout: None,
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert!(result.message.starts_with("SSH-2.0-"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_ssh_fingerprint_mismatch() -> Result<()> {
+    let handler = SshHandler { check: &Check::default() };
+    let spec = Ssh {
+      id: 0,
+      check_id: 0,
+      host: "github.com".to_string(),
+      port: 22,
+      algorithm: Some("ssh-ed25519".to_string()),
+      fingerprint: Some("SHA256:47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU".to_string()),
+      timeout: None,
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("host key fingerprint mismatch"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_ssh_invalid_banner() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+
+      stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await.unwrap();
+    });
+
+    let handler = SshHandler { check: &Check::default() };
+    let spec = Ssh {
+      id: 0,
+      check_id: 0,
+      host: addr.ip().to_string(),
+      port: addr.port(),
+      algorithm: None,
+      fingerprint: None,
+      timeout: Some(1.into()),
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("SSH handshake failed"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_ssh_invalid() {
+    let handler = SshHandler { check: &Check::default() };
+    let spec = Ssh {
+      id: 0,
+      check_id: 0,
+      host: "300.300.300.300".to_string(),
+      port: 22,
+      algorithm: None,
+      fingerprint: None,
+      timeout: None,
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
+    assert!(result.is_err());
+  }
+}
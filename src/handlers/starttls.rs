@@ -0,0 +1,244 @@
+use std::pin::Pin;
+
+use anyhow::{Context, Result};
+use openssl::ssl::{SslConnector, SslMethod};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_openssl::SslStream;
+
+use crate::model::specs::StartTlsProtocol;
+
+pub async fn upgrade<S>(stream: S, domain: &str) -> Result<SslStream<S>>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  let ssl = SslConnector::builder(SslMethod::tls_client())?.build().configure()?.into_ssl(domain)?;
+  let mut stream = SslStream::new(ssl, stream)?;
+
+  Pin::new(&mut stream).connect().await.context("TLS handshake failed")?;
+
+  Ok(stream)
+}
+
+/// Perform the plaintext negotiation of `protocol` on `stream`, leaving it ready for a TLS handshake.
+pub async fn negotiate<S>(stream: &mut S, protocol: StartTlsProtocol, domain: &str) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  match protocol {
+    StartTlsProtocol::Smtp => smtp(stream).await,
+    StartTlsProtocol::Imap => imap(stream).await,
+    StartTlsProtocol::Ldap => ldap(stream).await,
+    StartTlsProtocol::Postgres => postgres(stream).await,
+    StartTlsProtocol::Xmpp => xmpp(stream, domain).await,
+  }
+  .context("STARTTLS negotiation failed")
+}
+
+async fn smtp<S>(stream: &mut S) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  let banner = reply(stream).await?;
+
+  if !banner.starts_with("220") {
+    return Err(anyhow!("unexpected banner: {banner}"));
+  }
+
+  stream.write_all(b"EHLO defcon\r\n").await?;
+
+  let ehlo = reply(stream).await?;
+
+  if !ehlo.starts_with("250") {
+    return Err(anyhow!("unexpected EHLO reply: {ehlo}"));
+  }
+
+  stream.write_all(b"STARTTLS\r\n").await?;
+
+  let reply = reply(stream).await?;
+
+  match reply.starts_with("220") {
+    true => Ok(()),
+    false => Err(anyhow!("server refused STARTTLS: {reply}")),
+  }
+}
+
+async fn imap<S>(stream: &mut S) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  let greeting = line(stream).await?;
+
+  if !greeting.starts_with("* OK") {
+    return Err(anyhow!("unexpected greeting: {greeting}"));
+  }
+
+  stream.write_all(b"a0 STARTTLS\r\n").await?;
+
+  loop {
+    let line = line(stream).await?;
+
+    if let Some(status) = line.strip_prefix("a0 ") {
+      return match status.starts_with("OK") {
+        true => Ok(()),
+        false => Err(anyhow!("server refused STARTTLS: {status}")),
+      };
+    }
+  }
+}
+
+/// StartTLS extended request (RFC 4511, section 4.14.1) with message ID 1.
+const LDAP_STARTTLS: &[u8] = b"\x30\x1d\x02\x01\x01\x77\x18\x80\x161.3.6.1.4.1.1466.20037";
+
+async fn ldap<S>(stream: &mut S) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  stream.write_all(LDAP_STARTTLS).await?;
+
+  let (tag, message) = ber(stream).await?;
+
+  if tag != 0x30 {
+    return Err(anyhow!("unexpected LDAP message"));
+  }
+
+  // Skip the message ID, then look for the result code of the extended response.
+  let (_, _, rest) = tlv(&message)?;
+  let (tag, response, _) = tlv(rest)?;
+  let (_, code, _) = tlv(response)?;
+
+  match (tag, code) {
+    (0x78, [0]) => Ok(()),
+    (0x78, [code]) => Err(anyhow!("server refused STARTTLS with result code {code}")),
+    _ => Err(anyhow!("unexpected LDAP response")),
+  }
+}
+
+/// SSLRequest message (length 8, code 80877103).
+const POSTGRES_SSL_REQUEST: &[u8] = &[0, 0, 0, 8, 0x04, 0xd2, 0x16, 0x2f];
+
+async fn postgres<S>(stream: &mut S) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  stream.write_all(POSTGRES_SSL_REQUEST).await?;
+
+  match stream.read_u8().await? {
+    b'S' => Ok(()),
+    b'N' => Err(anyhow!("server does not support TLS")),
+    _ => Err(anyhow!("unexpected response to SSLRequest")),
+  }
+}
+
+async fn xmpp<S>(stream: &mut S, domain: &str) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  let header = format!("<?xml version='1.0'?><stream:stream to='{domain}' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' version='1.0'>");
+
+  stream.write_all(header.as_bytes()).await?;
+
+  let features = until(stream, &["</stream:features>", "<stream:features/>"]).await?;
+
+  if !features.contains("<starttls") {
+    return Err(anyhow!("server does not offer STARTTLS"));
+  }
+
+  stream.write_all(b"<starttls xmlns='urn:ietf:params:xml:ns:xmpp-tls'/>").await?;
+
+  let reply = until(stream, &["<proceed", "<failure"]).await?;
+
+  match reply.contains("<proceed") {
+    true => Ok(()),
+    false => Err(anyhow!("server refused STARTTLS")),
+  }
+}
+
+/// Read a single line, one byte at a time so nothing meant for the TLS handshake gets buffered.
+async fn line<S>(stream: &mut S) -> Result<String>
+where
+  S: AsyncRead + Unpin,
+{
+  let mut line = Vec::new();
+
+  loop {
+    match stream.read_u8().await? {
+      b'\n' => break,
+      byte => line.push(byte),
+    }
+  }
+
+  Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+}
+
+/// Read a possibly multiline SMTP reply, and return its last line.
+async fn reply<S>(stream: &mut S) -> Result<String>
+where
+  S: AsyncRead + Unpin,
+{
+  loop {
+    let line = line(stream).await?;
+
+    if line.as_bytes().get(3) != Some(&b'-') {
+      return Ok(line);
+    }
+  }
+}
+
+async fn until<S>(stream: &mut S, markers: &[&str]) -> Result<String>
+where
+  S: AsyncRead + Unpin,
+{
+  let mut buffer = Vec::new();
+
+  loop {
+    buffer.push(stream.read_u8().await?);
+
+    let text = String::from_utf8_lossy(&buffer);
+
+    // Stop at the end of a tag only, so the rest of the element isn't mistaken for the TLS handshake.
+    if text.ends_with('>') && markers.iter().any(|marker| text.contains(marker)) {
+      return Ok(text.to_string());
+    }
+  }
+}
+
+async fn ber<S>(stream: &mut S) -> Result<(u8, Vec<u8>)>
+where
+  S: AsyncRead + Unpin,
+{
+  let tag = stream.read_u8().await?;
+  let length = match stream.read_u8().await? {
+    length if length & 0x80 == 0 => length as usize,
+    length => {
+      let mut bytes = vec![0; (length & 0x7f) as usize];
+      stream.read_exact(&mut bytes).await?;
+      bytes.iter().fold(0, |length, byte| (length << 8) | *byte as usize)
+    }
+  };
+
+  let mut value = vec![0; length];
+  stream.read_exact(&mut value).await?;
+
+  Ok((tag, value))
+}
+
+/// Split the first BER element off `data`, returning its tag, value and the remaining bytes.
+fn tlv(data: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+  let invalid = || anyhow!("invalid LDAP response");
+
+  let (&tag, data) = data.split_first().ok_or_else(invalid)?;
+  let (&length, data) = data.split_first().ok_or_else(invalid)?;
+
+  let (length, data) = match length & 0x80 {
+    0 => (length as usize, data),
+    _ => {
+      let count = (length & 0x7f) as usize;
+      let bytes = data.get(..count).ok_or_else(invalid)?;
+      (bytes.iter().fold(0, |length, byte| (length << 8) | *byte as usize), &data[count..])
+    }
+  };
+
+  let value = data.get(..length).ok_or_else(invalid)?;
+
+  Ok((tag, value, &data[length..]))
+}
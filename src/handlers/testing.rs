@@ -0,0 +1,110 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::Result;
+use tokio::{
+  io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+  net::TcpListener,
+};
+
+/// Request received by the test server.
+pub struct Request {
+  /// Request line and headers, as sent by the client.
+  pub head: String,
+  pub body: String,
+}
+
+impl Request {
+  /// Request line, such as `GET / HTTP/1.1`.
+  pub fn line(&self) -> &str {
+    self.head.lines().next().unwrap_or_default()
+  }
+
+  /// Path requested, without the query string.
+  pub fn path(&self) -> &str {
+    let target = self.line().split_whitespace().nth(1).unwrap_or_default();
+
+    target.split('?').next().unwrap_or_default()
+  }
+
+  /// Value of the first header with the given name, which is case-insensitive.
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self.head.lines().skip(1).find_map(|line| {
+      let (header, value) = line.split_once(':')?;
+
+      header.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+  }
+
+  /// The whole request, for responses echoing it back.
+  pub fn echo(&self) -> String {
+    format!("{}{}", self.head, self.body)
+  }
+}
+
+/// Serve HTTP on a local port, answering every request with the status line and headers, and the body, returned by `handler`.
+pub async fn serve<F, H, B>(handler: F) -> Result<SocketAddr>
+where
+  F: Fn(&Request) -> (H, B) + Send + Sync + 'static,
+  H: Into<String>,
+  B: Into<String>,
+{
+  let listener = TcpListener::bind("127.0.0.1:0").await?;
+  let addr = listener.local_addr()?;
+  let handler = Arc::new(handler);
+
+  tokio::spawn(async move {
+    while let Ok((stream, _)) = listener.accept().await {
+      let handler = handler.clone();
+
+      tokio::spawn(async move {
+        let _ = respond(stream, |request| handler(request)).await;
+      });
+    }
+  });
+
+  Ok(addr)
+}
+
+/// Read a single request from the stream and write back the response returned by `handler`.
+pub async fn respond<S, F, H, B>(stream: S, handler: F) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+  F: FnOnce(&Request) -> (H, B),
+  H: Into<String>,
+  B: Into<String>,
+{
+  let mut stream = BufReader::new(stream);
+  let mut head = String::new();
+  let mut length = 0;
+
+  loop {
+    let mut line = String::new();
+    stream.read_line(&mut line).await?;
+
+    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+      length = value.trim().parse()?;
+    }
+
+    head.push_str(&line);
+
+    if line.trim().is_empty() {
+      break;
+    }
+  }
+
+  let mut body = vec![0; length];
+  stream.read_exact(&mut body).await?;
+
+  let request = Request {
+    head,
+    body: String::from_utf8_lossy(&body).to_string(),
+  };
+
+  let (status, body) = handler(&request);
+  let (status, body) = (status.into(), body.into());
+
+  let response = format!("HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}", body.len());
+  stream.write_all(response.as_bytes()).await?;
+
+  Ok(())
+}
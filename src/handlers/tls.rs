@@ -1,14 +1,25 @@
-use std::sync::Arc;
+use std::{net::IpAddr, pin::Pin, sync::Arc};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as b64, Engine as _};
+use openssl::{
+  asn1::Asn1Time,
+  hash::MessageDigest,
+  ocsp::{OcspCertId, OcspCertStatus, OcspFlag, OcspResponse, OcspResponseStatus},
+  sha::sha256,
+  ssl::{SslConnector, SslMethod, SslRef, SslVerifyMode, StatusType},
+  stack::Stack,
+  x509::{store::X509StoreBuilder, X509Ref, X509VerifyResult},
+};
 use sqlx::MySqlConnection;
-use ssl_expiration2::SslExpiration;
+use tokio::{net::TcpStream, time};
+use tokio_openssl::SslStream;
 
 use crate::{
   config::Config,
-  handlers::Handler,
-  model::{specs::Tls, status::*, Check, Event},
+  handlers::{starttls, Handler},
+  model::{specs::Tls, status::*, Check, Duration, Event},
   stash::Stash,
 };
 
@@ -26,13 +37,48 @@ impl Handler for TlsHandler<'_> {
     self.run(&spec, site, stash).await
   }
 
-  async fn run(&self, spec: &Tls, site: &str, _stash: Stash) -> Result<Event> {
-    let expiration = SslExpiration::from_domain_name(&spec.domain).map_err(|err| anyhow!("{}", err)).context("could not fetch certificate")?;
+  async fn run(&self, spec: &Tls, site: &str, mut stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+
+    let mut stream = time::timeout(*timeout, TcpStream::connect((spec.domain.as_str(), spec.port)))
+      .await
+      .context("could not connect")?
+      .context("could not connect")?;
 
-    let (status, message) = if expiration.secs() > 0 && expiration.secs() as u64 > spec.window.as_secs() {
-      (OK, format!("Certificate expires in {} days", expiration.days()))
-    } else {
-      (CRITICAL, format!("Certificate expires in {} days", expiration.days()))
+    let connection = async {
+      if let Some(protocol) = spec.starttls {
+        starttls::negotiate(&mut stream, protocol, &spec.domain).await?;
+      }
+
+      handshake(stream, spec).await
+    };
+
+    let (status, message) = match time::timeout(*timeout, connection).await {
+      Ok(Ok(stream)) => {
+        let (mut failures, fingerprint, expiry) = {
+          let ssl = stream.ssl();
+          let certificate = ssl.peer_certificate().ok_or_else(|| anyhow!("no certificate was presented"))?;
+
+          (validate(spec, ssl, &certificate)?, fingerprint(&certificate)?, expiration(&certificate)?.1)
+        };
+
+        let change = match spec.learn {
+          true => learn(&mut stash, self.check, &fingerprint).await,
+          false => None,
+        };
+
+        match (failures.is_empty(), change) {
+          (true, None) => (OK, expiry),
+          (true, Some(change)) => (WARNING, change),
+          (false, change) => {
+            failures.extend(change);
+            (CRITICAL, failures.join("; "))
+          }
+        }
+      }
+
+      Ok(Err(err)) => (CRITICAL, format!("{err:#}")),
+      Err(err) => (CRITICAL, err.to_string()),
     };
 
     let event = Event {
@@ -47,27 +93,563 @@ impl Handler for TlsHandler<'_> {
   }
 }
 
+async fn handshake(stream: TcpStream, spec: &Tls) -> Result<SslStream<TcpStream>> {
+  let mut builder = SslConnector::builder(SslMethod::tls_client())?;
+
+  // Verification is performed after the handshake, so each failure can be reported on its own.
+  builder.set_verify(SslVerifyMode::NONE);
+
+  let mut ssl = builder.build().configure()?.verify_hostname(false).into_ssl(&spec.domain)?;
+
+  if spec.ocsp {
+    ssl.set_status_type(StatusType::OCSP)?;
+  }
+
+  let mut stream = SslStream::new(ssl, stream)?;
+
+  Pin::new(&mut stream).connect().await.context("TLS handshake failed")?;
+
+  Ok(stream)
+}
+
+/// Run every assertion from the spec, and return the reasons for which the certificate is invalid.
+fn validate(spec: &Tls, ssl: &SslRef, certificate: &X509Ref) -> Result<Vec<String>> {
+  let mut failures = Vec::new();
+
+  let (remaining, message) = expiration(certificate)?;
+
+  if remaining <= 0 || remaining as u64 <= spec.window.as_secs() {
+    failures.push(message);
+  }
+
+  if spec.verify_hostname && !matches_hostname(certificate, &spec.domain) {
+    failures.push(format!("certificate does not match {}", spec.domain));
+  }
+
+  if spec.verify_chain {
+    let result = ssl.verify_result();
+
+    if result != X509VerifyResult::OK {
+      failures.push(format!("certificate chain is not trusted: {}", result.error_string()));
+    }
+  }
+
+  if let Some(ref expected) = spec.issuer {
+    let issuer = certificate
+      .issuer_name()
+      .entries()
+      .map(|entry| format!("{}={}", entry.object().nid().short_name().unwrap_or("?"), String::from_utf8_lossy(entry.data().as_slice())))
+      .collect::<Vec<_>>()
+      .join(", ");
+
+    if !issuer.to_lowercase().contains(&expected.to_lowercase()) {
+      failures.push(format!("issuer is {issuer}, expected {expected}"));
+    }
+  }
+
+  if let Some(minimum) = spec.min_key_size {
+    let bits = certificate.public_key()?.bits();
+
+    if bits < minimum {
+      failures.push(format!("key size is {bits} bits, expected at least {minimum}"));
+    }
+  }
+
+  if let Some(ref expected) = spec.signature_algorithm {
+    let nid = certificate.signature_algorithm().object().nid();
+    let long = nid.long_name().unwrap_or("unknown");
+    let short = nid.short_name().unwrap_or("unknown");
+
+    if !long.eq_ignore_ascii_case(expected) && !short.eq_ignore_ascii_case(expected) {
+      failures.push(format!("signature algorithm is {long}, expected {expected}"));
+    }
+  }
+
+  if let Some(ref expected) = spec.fingerprint {
+    let fingerprint = fingerprint(certificate)?;
+
+    if expected.trim_start_matches("sha256/") != fingerprint.trim_start_matches("sha256/") {
+      failures.push(format!("public key fingerprint mismatch: expected {expected}, got {fingerprint}"));
+    }
+  }
+
+  if spec.ocsp {
+    if let Err(failure) = ocsp(ssl, certificate) {
+      failures.push(failure);
+    }
+  }
+
+  Ok(failures)
+}
+
+/// Compute the number of seconds before the certificate expires, along with a human-readable message.
+fn expiration(certificate: &X509Ref) -> Result<(i64, String)> {
+  let diff = Asn1Time::days_from_now(0)?.diff(certificate.not_after())?;
+  let remaining = diff.days as i64 * 86400 + diff.secs as i64;
+
+  Ok((remaining, format!("Certificate expires in {} days", diff.days)))
+}
+
+/// Compute the SHA256 fingerprint of the certificate public key (SPKI), in the format used by most pinning libraries.
+fn fingerprint(certificate: &X509Ref) -> Result<String> {
+  let spki = certificate.public_key()?.public_key_to_der()?;
+
+  Ok(format!("sha256/{}", b64.encode(sha256(&spki))))
+}
+
+/// Remember the fingerprint seen for the check, and describe how it changed since the previous run, if it did. The stash
+/// only lives in memory, so nothing is reported for the first run after a restart.
+async fn learn(stash: &mut Stash, check: &Check, fingerprint: &str) -> Option<String> {
+  let previous = stash.retrieve(check, "fingerprint").await;
+
+  stash.stash(check, "fingerprint", fingerprint).await;
+
+  previous
+    .filter(|previous| previous != fingerprint)
+    .map(|previous| format!("certificate changed: public key fingerprint was {previous}, now {fingerprint}"))
+}
+
+fn matches_hostname(certificate: &X509Ref, domain: &str) -> bool {
+  let domain = domain.trim_end_matches('.').to_lowercase();
+  let ip = domain.parse::<IpAddr>().ok();
+
+  let matches = |pattern: &str| {
+    let pattern = pattern.to_lowercase();
+
+    match pattern.strip_prefix("*.") {
+      Some(suffix) => domain.split_once('.').is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+      None => pattern == domain,
+    }
+  };
+
+  match certificate.subject_alt_names() {
+    Some(names) => names.iter().any(|name| match (name.dnsname(), name.ipaddress(), ip) {
+      (Some(dns), _, None) => matches(dns),
+      (_, Some(bytes), Some(IpAddr::V4(ip))) => bytes == ip.octets(),
+      (_, Some(bytes), Some(IpAddr::V6(ip))) => bytes == ip.octets(),
+      _ => false,
+    }),
+
+    None => certificate
+      .subject_name()
+      .entries_by_nid(openssl::nid::Nid::COMMONNAME)
+      .any(|entry| entry.data().as_utf8().map(|name| matches(&name)).unwrap_or(false)),
+  }
+}
+
+fn ocsp(ssl: &SslRef, certificate: &X509Ref) -> Result<(), String> {
+  let response = ssl.ocsp_status().ok_or_else(|| "no stapled OCSP response".to_string())?;
+  let response = OcspResponse::from_der(response).map_err(|_| "stapled OCSP response is invalid".to_string())?;
+
+  if response.status() != OcspResponseStatus::SUCCESSFUL {
+    return Err("stapled OCSP response is not successful".to_string());
+  }
+
+  let basic = response.basic().map_err(|_| "stapled OCSP response is invalid".to_string())?;
+  let chain = ssl.peer_cert_chain().ok_or_else(|| "no certificate chain was presented".to_string())?;
+  let issuer = chain
+    .iter()
+    .find(|candidate| candidate.issued(certificate) == X509VerifyResult::OK)
+    .ok_or_else(|| "issuer certificate was not presented".to_string())?;
+
+  let verified = (|| -> Result<(), openssl::error::ErrorStack> {
+    let mut store = X509StoreBuilder::new()?;
+    store.set_default_paths()?;
+
+    let mut certificates = Stack::new()?;
+
+    for certificate in chain {
+      certificates.push(certificate.to_owned())?;
+    }
+
+    basic.verify(&certificates, &store.build(), OcspFlag::empty())
+  })();
+
+  if verified.is_err() {
+    return Err("stapled OCSP response signature is invalid".to_string());
+  }
+
+  let id = OcspCertId::from_cert(MessageDigest::sha1(), certificate, issuer).map_err(|_| "could not identify certificate for OCSP".to_string())?;
+  let status = basic.find_status(&id).ok_or_else(|| "stapled OCSP response does not cover the certificate".to_string())?;
+
+  if status.check_validity(300, None).is_err() {
+    return Err("stapled OCSP response is outdated".to_string());
+  }
+
+  match status.status {
+    OcspCertStatus::GOOD => Ok(()),
+    OcspCertStatus::REVOKED => Err("certificate is revoked".to_string()),
+    _ => Err("certificate revocation status is unknown".to_string()),
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use std::convert::TryFrom;
+  use std::{convert::TryFrom, net::SocketAddr, pin::Pin};
+
+  use anyhow::Result;
+  use openssl::{
+    asn1::Asn1Time,
+    bn::{BigNum, MsbOption},
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    rsa::Rsa,
+    ssl::{SslAcceptor, SslMethod},
+    x509::{extension::SubjectAlternativeName, X509NameBuilder, X509},
+  };
+  use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+  };
+  use tokio_openssl::SslStream;
 
   use super::{Handler, TlsHandler};
   use crate::{
     config::CONTROLLER_ID,
-    model::{specs::Tls, status::*, Check, Duration},
+    model::{
+      specs::{StartTlsProtocol, Tls},
+      status::*,
+      Check, Duration,
+    },
     stash::Stash,
   };
 
+  fn spec(domain: &str, window: Duration) -> Tls {
+    Tls {
+      id: 0,
+      check_id: 0,
+      domain: domain.to_string(),
+      port: 443,
+      window,
+      verify_hostname: false,
+      verify_chain: false,
+      issuer: None,
+      min_key_size: None,
+      signature_algorithm: None,
+      ocsp: false,
+      fingerprint: None,
+      learn: false,
+      starttls: None,
+      timeout: Some(Duration::from(5)),
+    }
+  }
+
+  fn certificate(name: &str) -> Result<(X509, PKey<Private>)> {
+    let key = PKey::from_rsa(Rsa::generate(2048)?)?;
+
+    let mut subject = X509NameBuilder::new()?;
+    subject.append_entry_by_text("CN", name)?;
+    subject.append_entry_by_text("O", "Defcon Test")?;
+    let subject = subject.build();
+
+    let mut serial = BigNum::new()?;
+    serial.rand(64, MsbOption::MAYBE_ZERO, false)?;
+
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    let serial = serial.to_asn1_integer()?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(30)?;
+
+    builder.set_serial_number(&serial)?;
+    builder.set_subject_name(&subject)?;
+    builder.set_issuer_name(&subject)?;
+    builder.set_pubkey(&key)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+
+    let san = SubjectAlternativeName::new().dns(name).build(&builder.x509v3_context(None, None))?;
+    builder.append_extension(san)?;
+    builder.sign(&key, MessageDigest::sha256())?;
+
+    Ok((builder.build(), key))
+  }
+
+  async fn server(name: &str) -> Result<SocketAddr> {
+    scripted(name, &[]).await
+  }
+
+  /// Start a TLS server which first plays the plaintext `script`: for each step, wait for the client to send
+  /// something containing the expected bytes (if any), then write the response.
+  async fn scripted(name: &str, script: &'static [(Option<&'static [u8]>, &'static [u8])]) -> Result<SocketAddr> {
+    let (certificate, key) = certificate(name)?;
+
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+    acceptor.set_certificate(&certificate)?;
+    acceptor.set_private_key(&key)?;
+    let acceptor = acceptor.build();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+
+      for (expected, response) in script {
+        if let Some(expected) = expected {
+          let mut received = Vec::new();
+
+          while !received.windows(expected.len()).any(|window| window == *expected) {
+            received.push(stream.read_u8().await.unwrap());
+          }
+        }
+
+        stream.write_all(response).await.unwrap();
+      }
+
+      let ssl = openssl::ssl::Ssl::new(acceptor.context()).unwrap();
+      let mut stream = SslStream::new(ssl, stream).unwrap();
+
+      let _ = Pin::new(&mut stream).accept().await;
+      let _ = stream.read(&mut [0; 1]).await;
+    });
+
+    Ok(addr)
+  }
+
+  fn local(addr: SocketAddr, window: Duration) -> Tls {
+    Tls {
+      port: addr.port(),
+      ..spec(&addr.ip().to_string(), window)
+    }
+  }
+
   #[tokio::test]
-  async fn handler_tls_ok() {
+  async fn handler_tls_local_ok() -> Result<()> {
+    let addr = server("localhost").await?;
+
+    let handler = TlsHandler { check: &Check::default() };
+    let result = handler.run(&local(addr, Duration::try_from("7 days")?), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+    assert!(result.message.starts_with("Certificate expires in"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_local_window() -> Result<()> {
+    let addr = server("localhost").await?;
+
+    let handler = TlsHandler { check: &Check::default() };
+    let result = handler.run(&local(addr, Duration::try_from("60 days")?), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with("Certificate expires in"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_local_assertions() -> Result<()> {
+    let addr = server("example.com").await?;
+
     let handler = TlsHandler { check: &Check::default() };
     let spec = Tls {
-      id: 0,
-      check_id: 0,
-      domain: "letsencrypt.org".to_string(),
-      window: Duration::try_from("0 days").unwrap(),
+      verify_hostname: true,
+      verify_chain: true,
+      issuer: Some("Let's Encrypt".to_string()),
+      min_key_size: Some(4096),
+      signature_algorithm: Some("ecdsa-with-SHA384".to_string()),
+      ocsp: true,
+      ..local(addr, Duration::try_from("7 days")?)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+    let failures = result.message.split("; ").collect::<Vec<_>>();
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(
+      failures,
+      vec![
+        "certificate does not match 127.0.0.1",
+        "certificate chain is not trusted: self-signed certificate",
+        "issuer is CN=example.com, O=Defcon Test, expected Let's Encrypt",
+        "key size is 2048 bits, expected at least 4096",
+        "signature algorithm is sha256WithRSAEncryption, expected ecdsa-with-SHA384",
+        "no stapled OCSP response",
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_local_hostname() -> Result<()> {
+    let addr = server("localhost").await?;
+
+    let handler = TlsHandler { check: &Check::default() };
+    let spec = Tls {
+      domain: "localhost".to_string(),
+      verify_hostname: true,
+      ..local(addr, Duration::try_from("7 days")?)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_learn() -> Result<()> {
+    let check = Check::default();
+    let handler = TlsHandler { check: &check };
+    let stash = Stash::new();
+
+    let first = server("localhost").await?;
+    let spec = Tls {
+      learn: true,
+      ..local(first, Duration::try_from("7 days")?)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, stash.clone()).await?;
+    let learned = stash.retrieve(&check, "fingerprint").await.unwrap();
+
+    assert_eq!(result.status, OK);
+    assert!(learned.starts_with("sha256/"));
+
+    let second = server("localhost").await?;
+    let result = handler.run(&Tls { port: second.port(), ..spec }, CONTROLLER_ID, stash.clone()).await?;
+    let current = stash.retrieve(&check, "fingerprint").await.unwrap();
+
+    assert_eq!(result.status, WARNING);
+    assert_eq!(result.message, format!("certificate changed: public key fingerprint was {learned}, now {current}"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_fingerprint() -> Result<()> {
+    let check = Check::default();
+    let handler = TlsHandler { check: &check };
+    let stash = Stash::new();
+
+    let addr = server("localhost").await?;
+    let spec = Tls {
+      learn: true,
+      ..local(addr, Duration::try_from("7 days")?)
+    };
+
+    handler.run(&spec, CONTROLLER_ID, stash.clone()).await?;
+    let fingerprint = stash.retrieve(&check, "fingerprint").await.unwrap();
+
+    let addr = server("localhost").await?;
+    let spec = Tls {
+      fingerprint: Some(fingerprint.clone()),
+      ..local(addr, Duration::try_from("7 days")?)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert!(result.message.starts_with(&format!("public key fingerprint mismatch: expected {}, got sha256/", fingerprint)));
+
+    Ok(())
+  }
+
+  async fn starttls(protocol: StartTlsProtocol, script: &'static [(Option<&'static [u8]>, &'static [u8])]) -> Result<(u8, String)> {
+    let addr = scripted("localhost", script).await?;
+
+    let handler = TlsHandler { check: &Check::default() };
+    let spec = Tls {
+      starttls: Some(protocol),
+      ..local(addr, Duration::try_from("60 days")?)
     };
 
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    Ok((result.status, result.message))
+  }
+
+  #[tokio::test]
+  async fn handler_tls_starttls_smtp() -> Result<()> {
+    let script: &[(Option<&[u8]>, &[u8])] = &[
+      (None, b"220-mx.example.com ESMTP\r\n220 ready\r\n"),
+      (Some(b"EHLO defcon\r\n"), b"250-mx.example.com\r\n250 STARTTLS\r\n"),
+      (Some(b"STARTTLS\r\n"), b"220 go ahead\r\n"),
+    ];
+
+    let (status, message) = starttls(StartTlsProtocol::Smtp, script).await?;
+
+    assert_eq!(status, CRITICAL);
+    assert!(message.starts_with("Certificate expires in"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_starttls_imap() -> Result<()> {
+    let script: &[(Option<&[u8]>, &[u8])] = &[(None, b"* OK IMAP4rev1 ready\r\n"), (Some(b"a0 STARTTLS\r\n"), b"a0 OK begin TLS\r\n")];
+
+    let (status, message) = starttls(StartTlsProtocol::Imap, script).await?;
+
+    assert_eq!(status, CRITICAL);
+    assert!(message.starts_with("Certificate expires in"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_starttls_ldap() -> Result<()> {
+    let script: &[(Option<&[u8]>, &[u8])] = &[(Some(b"1.3.6.1.4.1.1466.20037"), b"\x30\x0c\x02\x01\x01\x78\x07\x0a\x01\x00\x04\x00\x04\x00")];
+
+    let (status, message) = starttls(StartTlsProtocol::Ldap, script).await?;
+
+    assert_eq!(status, CRITICAL);
+    assert!(message.starts_with("Certificate expires in"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_starttls_postgres() -> Result<()> {
+    let script: &[(Option<&[u8]>, &[u8])] = &[(Some(&[0, 0, 0, 8, 0x04, 0xd2, 0x16, 0x2f]), b"S")];
+
+    let (status, message) = starttls(StartTlsProtocol::Postgres, script).await?;
+
+    assert_eq!(status, CRITICAL);
+    assert!(message.starts_with("Certificate expires in"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_starttls_xmpp() -> Result<()> {
+    let script: &[(Option<&[u8]>, &[u8])] = &[
+      (
+        Some(b"version='1.0'>"),
+        b"<?xml version='1.0'?><stream:stream from='localhost' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' version='1.0'><stream:features><starttls xmlns='urn:ietf:params:xml:ns:xmpp-tls'><required/></starttls></stream:features>",
+      ),
+      (Some(b"<starttls xmlns='urn:ietf:params:xml:ns:xmpp-tls'/>"), b"<proceed xmlns='urn:ietf:params:xml:ns:xmpp-tls'/>"),
+    ];
+
+    let (status, message) = starttls(StartTlsProtocol::Xmpp, script).await?;
+
+    assert_eq!(status, CRITICAL);
+    assert!(message.starts_with("Certificate expires in"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_starttls_refused() -> Result<()> {
+    let script: &[(Option<&[u8]>, &[u8])] = &[(Some(&[0, 0, 0, 8, 0x04, 0xd2, 0x16, 0x2f]), b"N")];
+
+    let (status, message) = starttls(StartTlsProtocol::Postgres, script).await?;
+
+    assert_eq!(status, CRITICAL);
+    assert_eq!(message, "STARTTLS negotiation failed: server does not support TLS");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_tls_ok() {
+    let handler = TlsHandler { check: &Check::default() };
+    let spec = spec("letsencrypt.org", Duration::try_from("0 days").unwrap());
+
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
     assert!(matches!(&result, Ok(_)));
 
@@ -78,12 +660,7 @@ mod tests {
   #[tokio::test]
   async fn handler_tls_critical() {
     let handler = TlsHandler { check: &Check::default() };
-    let spec = Tls {
-      id: 0,
-      check_id: 0,
-      domain: "letsencrypt.org".to_string(),
-      window: Duration::try_from("91 days").unwrap(),
-    };
+    let spec = spec("letsencrypt.org", Duration::try_from("91 days").unwrap());
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
     assert!(matches!(&result, Ok(_)));
@@ -96,12 +673,7 @@ mod tests {
   #[tokio::test]
   async fn handler_tls_expired() {
     let handler = TlsHandler { check: &Check::default() };
-    let spec = Tls {
-      id: 0,
-      check_id: 0,
-      domain: "expired.badssl.com".to_string(),
-      window: Duration::from(1),
-    };
+    let spec = spec("expired.badssl.com", Duration::from(1));
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
     assert!(matches!(&result, Ok(_)));
@@ -114,12 +686,7 @@ mod tests {
   #[tokio::test]
   async fn handler_tls_invalid() {
     let handler = TlsHandler { check: &Check::default() };
-    let spec = Tls {
-      id: 0,
-      check_id: 0,
-      domain: "*".to_string(),
-      window: Duration::from(1),
-    };
+    let spec = spec("*", Duration::from(1));
 
     let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await;
     assert!(matches!(&result, Err(_)));
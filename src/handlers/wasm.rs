@@ -0,0 +1,439 @@
+use std::{
+  fs,
+  net::TcpStream,
+  sync::{mpsc, Arc},
+  thread,
+  time::Instant,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use sqlx::MySqlConnection;
+use wasmtime::{Caller, Config as EngineConfig, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+use wasmtime_wasi::{preview1::WasiP1Ctx, WasiCtxBuilder};
+
+use crate::{
+  config::Config,
+  handlers::{exec, http, script, Handler},
+  model::{
+    specs::{Http, Wasm},
+    status::*,
+    Check, Duration, Event,
+  },
+  stash::Stash,
+};
+
+/// Fuel granted to a plugin when none is configured, roughly the number of instructions it can execute.
+const DEFAULT_FUEL: u64 = 100_000_000;
+/// Size of the linear memory of a plugin when none is configured, in MiB.
+const DEFAULT_MEMORY: u32 = 64;
+/// Preamble of the binary format of WebAssembly components, which differ from core modules by their version and layer.
+const COMPONENT_PREAMBLE: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+
+pub struct WasmHandler<'h> {
+  pub check: &'h Check,
+  pub path: String,
+}
+
+struct State {
+  wasi: WasiP1Ctx,
+  limits: StoreLimits,
+  deadline: Instant,
+  message: String,
+}
+
+impl State {
+  /// Time left before the deadline of the invocation, which bounds the networking imports since the plugin cannot be
+  /// interrupted while the host runs them.
+  fn remaining(&self) -> Option<std::time::Duration> {
+    Some(self.deadline.saturating_duration_since(Instant::now())).filter(|remaining| !remaining.is_zero())
+  }
+}
+
+#[async_trait]
+impl Handler for WasmHandler<'_> {
+  type Spec = Wasm;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = Wasm::for_check(conn, self.check).await.context("no spec found for check")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &Wasm, site: &str, _stash: Stash) -> Result<Event> {
+    let path = self.path.clone();
+    let spec = spec.clone();
+
+    // Plugins are compiled and executed synchronously, and so are the networking imports they call.
+    let (status, message) = match tokio::task::spawn_blocking(move || invoke(&path, &spec)).await? {
+      Ok(result) => result,
+      Err(err) => (CRITICAL, format!("{err:#}")),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+/// Instantiate the module and call its `check()` export with the JSON configuration, within the fuel, memory and time limits.
+fn invoke(path: &str, spec: &Wasm) -> Result<(u8, String)> {
+  let bytes = match (&spec.plugin, &spec.module) {
+    (Some(plugin), None) => fs::read(exec::plugin(path, plugin)?).with_context(|| format!("could not read {plugin}"))?,
+    (None, Some(module)) => module.to_vec(),
+    _ => bail!("exactly one of `plugin` or `module` must be set"),
+  };
+
+  if bytes.starts_with(&COMPONENT_PREAMBLE) {
+    bail!("WebAssembly components are not supported, the plugin must be a core module");
+  }
+
+  let timeout = *spec.timeout.unwrap_or_else(|| Duration::from(5));
+  let memory = spec.memory.unwrap_or(DEFAULT_MEMORY) as usize * 1024 * 1024;
+
+  let mut config = EngineConfig::new();
+  config.consume_fuel(true).epoch_interruption(true);
+
+  let engine = Engine::new(&config)?;
+  let module = Module::new(&engine, &bytes).context("invalid WebAssembly module")?;
+
+  let mut linker = Linker::<State>::new(&engine);
+  wasmtime_wasi::preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi)?;
+  imports(&mut linker)?;
+
+  let state = State {
+    wasi: WasiCtxBuilder::new().build_p1(),
+    limits: StoreLimitsBuilder::new().memory_size(memory).instances(1).build(),
+    deadline: Instant::now() + timeout,
+    message: String::new(),
+  };
+
+  let mut store = Store::new(&engine, state);
+  store.limiter(|state| &mut state.limits);
+  store.set_fuel(spec.fuel.unwrap_or(DEFAULT_FUEL))?;
+  store.set_epoch_deadline(1);
+
+  // Interrupt the plugin once the timeout is reached, unless it returned before.
+  let (done, deadline) = mpsc::channel::<()>();
+  thread::spawn({
+    let engine = engine.clone();
+
+    move || {
+      if deadline.recv_timeout(timeout) == Err(mpsc::RecvTimeoutError::Timeout) {
+        engine.increment_epoch();
+      }
+    }
+  });
+
+  let result = call(&linker, &mut store, &module, spec).map_err(|err| match err.downcast_ref::<Trap>() {
+    Some(Trap::OutOfFuel) => anyhow!("plugin ran out of fuel"),
+    Some(Trap::Interrupt) => anyhow!("plugin timed out"),
+    _ => err,
+  });
+
+  drop(done);
+
+  let status = result?;
+  let status = u8::try_from(status)
+    .ok()
+    .filter(|status| [OK, WARNING, CRITICAL, UNKNOWN].contains(status))
+    .ok_or_else(|| anyhow!("plugin returned an invalid status"))?;
+
+  Ok((status, std::mem::take(&mut store.data_mut().message)))
+}
+
+fn call(linker: &Linker<State>, store: &mut Store<State>, module: &Module, spec: &Wasm) -> Result<i32> {
+  let instance = linker.instantiate(&mut *store, module).context("could not instantiate plugin")?;
+
+  // Modules compiled as WASI reactors need to be initialized before any other export is called.
+  if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut *store, "_initialize") {
+    initialize.call(&mut *store, ())?;
+  }
+
+  let config = spec.config.as_ref().map(|config| config.0.to_string()).unwrap_or_else(|| "{}".to_string());
+  let length = i32::try_from(config.len()).context("configuration is too large")?;
+
+  let memory = instance.get_memory(&mut *store, "memory").context("plugin does not export its memory")?;
+  let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "alloc").context("plugin does not export `alloc`")?;
+  let check = instance.get_typed_func::<(i32, i32), i32>(&mut *store, "check").context("plugin does not export `check`")?;
+
+  let pointer = alloc.call(&mut *store, length)?;
+  memory.write(&mut *store, pointer as usize, config.as_bytes()).context("could not pass configuration to plugin")?;
+
+  check.call(&mut *store, (pointer, length))
+}
+
+/// Register the functions plugins can import from the `defcon` module.
+fn imports(linker: &mut Linker<State>) -> Result<()> {
+  linker.func_wrap("defcon", "set_message", |mut caller: Caller<'_, State>, pointer: i32, length: i32| -> Result<()> {
+    caller.data_mut().message = read(&mut caller, pointer, length)?;
+
+    Ok(())
+  })?;
+
+  linker.func_wrap("defcon", "http_get", |mut caller: Caller<'_, State>, pointer: i32, length: i32| -> Result<i32> {
+    let request = Http {
+      url: read(&mut caller, pointer, length)?,
+      ..Default::default()
+    };
+
+    let Some(timeout) = caller.data().remaining() else { return Ok(-1) };
+
+    let code = http::agent(&request, timeout).and_then(|agent| http::request(&agent, &request)).map(|response| response.code as i32);
+
+    Ok(code.unwrap_or(-1))
+  })?;
+
+  linker.func_wrap("defcon", "tcp_connect", |mut caller: Caller<'_, State>, pointer: i32, length: i32, port: i32| -> Result<i32> {
+    let host = read(&mut caller, pointer, length)?;
+    let (Ok(port), Some(timeout)) = (u16::try_from(port), caller.data().remaining()) else {
+      return Ok(0);
+    };
+
+    // Each address gets whatever time is left, so trying them all cannot outlast the deadline.
+    let state = caller.data();
+    let connected = script::resolve(&host, port, timeout)
+      .iter()
+      .any(|addr| state.remaining().is_some_and(|timeout| TcpStream::connect_timeout(addr, timeout).is_ok()));
+
+    Ok(connected as i32)
+  })?;
+
+  linker.func_wrap("defcon", "dns_lookup", |mut caller: Caller<'_, State>, pointer: i32, length: i32| -> Result<i32> {
+    let name = read(&mut caller, pointer, length)?;
+    let Some(timeout) = caller.data().remaining() else { return Ok(0) };
+
+    Ok(script::resolve(&name, 0, timeout).len() as i32)
+  })?;
+
+  Ok(())
+}
+
+/// Read a UTF-8 string from the memory of the plugin.
+fn read(caller: &mut Caller<'_, State>, pointer: i32, length: i32) -> Result<String> {
+  let memory = caller.get_export("memory").and_then(|export| export.into_memory()).context("plugin does not export its memory")?;
+  let bytes = memory
+    .data(&caller)
+    .get(pointer as usize..(pointer as usize).saturating_add(length as usize))
+    .context("string is out of the bounds of memory")?;
+
+  Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Instant;
+
+  use anyhow::Result;
+  use sqlx::types::Json;
+  use tokio::net::TcpListener;
+
+  use super::{Handler, WasmHandler, COMPONENT_PREAMBLE};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{specs::Wasm, status::*, Check, Duration},
+    stash::Stash,
+  };
+
+  /// Plugin checking whether the port given as configuration accepts connections on localhost.
+  const PLUGIN: &str = r#"
+    (module
+      (import "defcon" "set_message" (func $set_message (param i32 i32)))
+      (import "defcon" "tcp_connect" (func $tcp_connect (param i32 i32 i32) (result i32)))
+
+      (memory (export "memory") 1)
+      (data (i32.const 0) "127.0.0.1")
+      (data (i32.const 16) "port is open")
+      (data (i32.const 32) "port is closed")
+
+      (func (export "alloc") (param $length i32) (result i32)
+        (i32.const 1024))
+
+      (func $port (param $pointer i32) (param $length i32) (result i32)
+        (local $port i32)
+        (local $end i32)
+        (local.set $end (i32.add (local.get $pointer) (local.get $length)))
+        (block $done
+          (loop $digits
+            (br_if $done (i32.ge_u (local.get $pointer) (local.get $end)))
+            (local.set $port (i32.add (i32.mul (local.get $port) (i32.const 10)) (i32.sub (i32.load8_u (local.get $pointer)) (i32.const 48))))
+            (local.set $pointer (i32.add (local.get $pointer) (i32.const 1)))
+            (br $digits)))
+        (local.get $port))
+
+      (func (export "check") (param $pointer i32) (param $length i32) (result i32)
+        (if (result i32) (call $tcp_connect (i32.const 0) (i32.const 9) (call $port (local.get $pointer) (local.get $length)))
+          (then (call $set_message (i32.const 16) (i32.const 12)) (i32.const 0))
+          (else (call $set_message (i32.const 32) (i32.const 14)) (i32.const 1)))))
+  "#;
+
+  const ECHO: &str = r#"
+    (module
+      (import "defcon" "set_message" (func $set_message (param i32 i32)))
+      (memory (export "memory") 1)
+      (func (export "alloc") (param $length i32) (result i32) (i32.const 0))
+      (func (export "check") (param $pointer i32) (param $length i32) (result i32)
+        (call $set_message (local.get $pointer) (local.get $length))
+        (i32.const 2)))
+  "#;
+
+  const LOOP: &str = r#"
+    (module
+      (memory (export "memory") 1)
+      (func (export "alloc") (param $length i32) (result i32) (i32.const 0))
+      (func (export "check") (param $pointer i32) (param $length i32) (result i32)
+        (loop $forever (br $forever))
+        (i32.const 0)))
+  "#;
+
+  /// Plugin requesting the URL given as configuration three times in a row, without any loop where it could be interrupted.
+  const SLOW: &str = r#"
+    (module
+      (import "defcon" "http_get" (func $http_get (param i32 i32) (result i32)))
+      (memory (export "memory") 1)
+      (func (export "alloc") (param $length i32) (result i32) (i32.const 0))
+      (func (export "check") (param $pointer i32) (param $length i32) (result i32)
+        (drop (call $http_get (i32.add (local.get $pointer) (i32.const 1)) (i32.sub (local.get $length) (i32.const 2))))
+        (drop (call $http_get (i32.add (local.get $pointer) (i32.const 1)) (i32.sub (local.get $length) (i32.const 2))))
+        (drop (call $http_get (i32.add (local.get $pointer) (i32.const 1)) (i32.sub (local.get $length) (i32.const 2))))
+        (i32.const 0)))
+  "#;
+
+  const GREEDY: &str = r#"
+    (module
+      (memory (export "memory") 128)
+      (func (export "alloc") (param $length i32) (result i32) (i32.const 0))
+      (func (export "check") (param $pointer i32) (param $length i32) (result i32) (i32.const 0)))
+  "#;
+
+  fn spec(module: &str) -> Wasm {
+    Wasm {
+      module: Some(module.as_bytes().into()),
+      timeout: Some(Duration::from(1)),
+      ..Default::default()
+    }
+  }
+
+  async fn run(spec: &Wasm) -> Result<(u8, String)> {
+    let handler = WasmHandler {
+      check: &Check::default(),
+      path: "/nonexistent".to_string(),
+    };
+
+    let result = handler.run(spec, CONTROLLER_ID, Stash::new()).await?;
+
+    Ok((result.status, result.message))
+  }
+
+  #[tokio::test]
+  async fn handler_wasm_imports() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+
+    let open = Wasm {
+      config: Some(Json(port.into())),
+      ..spec(PLUGIN)
+    };
+
+    assert_eq!(run(&open).await?, (OK, "port is open".to_string()));
+
+    drop(listener);
+
+    assert_eq!(run(&open).await?, (CRITICAL, "port is closed".to_string()));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_wasm_config() -> Result<()> {
+    let echo = Wasm {
+      config: Some(Json(serde_json::json!({ "lorem": "ipsum" }))),
+      ..spec(ECHO)
+    };
+
+    assert_eq!(run(&echo).await?, (WARNING, r#"{"lorem":"ipsum"}"#.to_string()));
+    assert_eq!(run(&spec(ECHO)).await?, (WARNING, "{}".to_string()));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_wasm_limits() -> Result<()> {
+    let fuel = Wasm { fuel: Some(1_000), ..spec(LOOP) };
+    assert_eq!(run(&fuel).await?, (CRITICAL, "plugin ran out of fuel".to_string()));
+
+    let time = Wasm { fuel: Some(u64::MAX), ..spec(LOOP) };
+    assert_eq!(run(&time).await?, (CRITICAL, "plugin timed out".to_string()));
+
+    let memory = Wasm { memory: Some(1), ..spec(GREEDY) };
+    let (status, message) = run(&memory).await?;
+    assert_eq!(status, CRITICAL);
+    assert!(message.starts_with("could not instantiate plugin"));
+
+    assert!(run(&spec(GREEDY)).await?.0 == OK);
+
+    Ok(())
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn handler_wasm_deadline() -> Result<()> {
+    // Accept connections but never answer, so each request blocks until its timeout.
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let mut streams = Vec::new();
+
+      while let Ok((stream, _)) = listener.accept().await {
+        streams.push(stream);
+      }
+    });
+
+    let slow = Wasm {
+      config: Some(Json(format!("http://{addr}/").into())),
+      ..spec(SLOW)
+    };
+
+    let start = Instant::now();
+    run(&slow).await?;
+
+    assert!(start.elapsed() < std::time::Duration::from_millis(1500));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_wasm_source() -> Result<()> {
+    let both = Wasm {
+      plugin: Some("check.wasm".to_string()),
+      ..spec(ECHO)
+    };
+
+    assert_eq!(run(&both).await?, (CRITICAL, "exactly one of `plugin` or `module` must be set".to_string()));
+
+    let plugin = Wasm {
+      plugin: Some("check.wasm".to_string()),
+      ..Default::default()
+    };
+
+    assert_eq!(run(&plugin).await?, (CRITICAL, "could not read plugins directory: No such file or directory (os error 2)".to_string()));
+
+    let component = Wasm {
+      module: Some(COMPONENT_PREAMBLE.to_vec().into()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      run(&component).await?,
+      (CRITICAL, "WebAssembly components are not supported, the plugin must be a core module".to_string())
+    );
+
+    Ok(())
+  }
+}
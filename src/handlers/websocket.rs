@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use sqlx::MySqlConnection;
+use tokio_tungstenite::tungstenite::{
+  client::IntoClientRequest,
+  http::{HeaderName, HeaderValue},
+  Error as WsError, Message,
+};
+
+use crate::{
+  config::Config,
+  handlers::Handler,
+  model::{specs::WebSocket, status::*, Check, Duration, Event},
+  stash::Stash,
+};
+
+pub struct WebSocketHandler<'h> {
+  pub check: &'h Check,
+}
+
+#[async_trait]
+impl Handler for WebSocketHandler<'_> {
+  type Spec = WebSocket;
+
+  async fn check(&self, conn: &mut MySqlConnection, _config: Arc<Config>, site: &str, stash: Stash) -> Result<Event> {
+    let spec = WebSocket::for_check(conn, self.check).await.context("no spec found")?;
+
+    self.run(&spec, site, stash).await
+  }
+
+  async fn run(&self, spec: &WebSocket, site: &str, _stash: Stash) -> Result<Event> {
+    let timeout = spec.timeout.unwrap_or_else(|| Duration::from(5));
+
+    let mut request = spec.url.as_str().into_client_request().context("could not parse URL")?;
+
+    for (header, value) in spec.headers.iter() {
+      let name = HeaderName::from_bytes(header.as_bytes()).with_context(|| format!("invalid header name: {header}"))?;
+      let value = HeaderValue::from_str(value).with_context(|| format!("invalid value for header {header}"))?;
+
+      request.headers_mut().insert(name, value);
+    }
+
+    let (status, message) = match tokio::time::timeout(*timeout, exchange(spec, request)).await {
+      Ok(Ok(())) => (OK, String::new()),
+      Ok(Err(err)) => (CRITICAL, format!("{err:#}")),
+      Err(err) => (CRITICAL, err.to_string()),
+    };
+
+    let event = Event {
+      check_id: self.check.id,
+      site: site.to_string(),
+      status,
+      message,
+      ..Default::default()
+    };
+
+    Ok(event)
+  }
+}
+
+async fn exchange(spec: &WebSocket, request: tokio_tungstenite::tungstenite::handshake::client::Request) -> Result<()> {
+  let (mut socket, _) = match tokio_tungstenite::connect_async(request).await {
+    Ok(socket) => socket,
+    Err(WsError::Http(response)) => return Err(anyhow!("upgrade failed: server responded with {}", response.status())),
+    Err(err) => return Err(anyhow!(err).context("could not connect")),
+  };
+
+  if let Some(ref message) = spec.message {
+    socket.send(Message::text(message.as_str())).await.context("could not send message")?;
+  }
+
+  if let Some(ref content) = spec.content {
+    let reply = loop {
+      match socket.next().await {
+        Some(Ok(Message::Text(text))) => break text.to_string(),
+        Some(Ok(Message::Binary(data))) => break String::from_utf8_lossy(&data).into_owned(),
+        Some(Ok(Message::Close(_))) | None => return Err(anyhow!("connection closed before a reply was received")),
+        Some(Ok(_)) => continue,
+        Some(Err(err)) => return Err(anyhow!(err).context("could not read reply")),
+      }
+    };
+
+    if !reply.contains(content.as_str()) {
+      return Err(anyhow!("reply does not contain `{content}`"));
+    }
+  }
+
+  let _ = socket.close(None).await;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashMap, net::SocketAddr};
+
+  use anyhow::Result;
+  use futures::{SinkExt, StreamExt};
+  use tokio::{io::AsyncWriteExt, net::TcpListener};
+  use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+
+  use super::{Handler, WebSocketHandler};
+  use crate::{
+    config::CONTROLLER_ID,
+    model::{
+      specs::{HttpHeaders, WebSocket},
+      status::*,
+      Check,
+    },
+    stash::Stash,
+  };
+
+  async fn echo() -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+
+      let check = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        match request.headers().get("authorization") {
+          Some(value) if value == "Bearer token" => Ok(response),
+          _ => Err(Response::builder().status(401).body(None).unwrap()),
+        }
+      };
+
+      let mut socket = tokio_tungstenite::accept_hdr_async(stream, check).await.unwrap();
+
+      while let Some(Ok(message)) = socket.next().await {
+        if message.is_text() && socket.send(message).await.is_err() {
+          break;
+        }
+      }
+    });
+
+    Ok(addr)
+  }
+
+  fn spec(addr: SocketAddr, authorization: bool) -> WebSocket {
+    let mut headers = HashMap::new();
+
+    if authorization {
+      headers.insert("authorization".to_string(), "Bearer token".to_string());
+    }
+
+    WebSocket {
+      id: 0,
+      check_id: 0,
+      url: format!("ws://{addr}/"),
+      headers: HttpHeaders(headers),
+      message: Some("ping".to_string()),
+      content: Some("ping".to_string()),
+      timeout: Some(1.into()),
+    }
+  }
+
+  #[tokio::test]
+  async fn handler_websocket_echo() -> Result<()> {
+    let addr = echo().await?;
+
+    let handler = WebSocketHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, true), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, OK);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_websocket_invalid_content() -> Result<()> {
+    let addr = echo().await?;
+
+    let handler = WebSocketHandler { check: &Check::default() };
+    let spec = WebSocket {
+      content: Some("pong".to_string()),
+      ..spec(addr, true)
+    };
+
+    let result = handler.run(&spec, CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "reply does not contain `pong`");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_websocket_rejected_upgrade() -> Result<()> {
+    let addr = echo().await?;
+
+    let handler = WebSocketHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, false), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "upgrade failed: server responded with 401 Unauthorized");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn handler_websocket_plain_http() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+
+      stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+    });
+
+    let handler = WebSocketHandler { check: &Check::default() };
+    let result = handler.run(&spec(addr, true), CONTROLLER_ID, Stash::new()).await?;
+
+    assert_eq!(result.status, CRITICAL);
+    assert_eq!(&result.message, "upgrade failed: server responded with 200 OK");
+
+    Ok(())
+  }
+}
@@ -333,12 +333,30 @@ impl Check {
       Tcp => specs::Tcp::for_check(conn, self).await.map(Spec::Tcp),
       Udp => specs::Udp::for_check(conn, self).await.map(Spec::Udp),
       Tls => specs::Tls::for_check(conn, self).await.map(Spec::Tls),
+      Smtp => specs::Smtp::for_check(conn, self).await.map(Spec::Smtp),
+      MailAccess => specs::MailAccess::for_check(conn, self).await.map(Spec::MailAccess),
+      Ssh => specs::Ssh::for_check(conn, self).await.map(Spec::Ssh),
+      Grpc => specs::Grpc::for_check(conn, self).await.map(Spec::Grpc),
+      WebSocket => specs::WebSocket::for_check(conn, self).await.map(Spec::WebSocket),
+      Database => specs::Database::for_check(conn, self).await.map(Spec::Database),
+      Ntp => specs::Ntp::for_check(conn, self).await.map(Spec::Ntp),
+      DnsConsistency => specs::DnsConsistency::for_check(conn, self).await.map(Spec::DnsConsistency),
+      Dnssec => specs::Dnssec::for_check(conn, self).await.map(Spec::Dnssec),
+      HttpFlow => specs::HttpFlow::for_check(conn, self).await.map(Spec::HttpFlow),
+      SecurityHeaders => specs::SecurityHeaders::for_check(conn, self).await.map(Spec::SecurityHeaders),
+      Crawl => specs::Crawl::for_check(conn, self).await.map(Spec::Crawl),
+      Prometheus => specs::Prometheus::for_check(conn, self).await.map(Spec::Prometheus),
+      Exec => specs::Exec::for_check(conn, self).await.map(Spec::Exec),
+      Script => specs::Script::for_check(conn, self).await.map(Spec::Script),
+      #[cfg(feature = "wasm")]
+      Wasm => specs::Wasm::for_check(conn, self).await.map(Spec::Wasm),
       PlayStore => specs::PlayStore::for_check(conn, self).await.map(Spec::PlayStore),
       AppStore => specs::AppStore::for_check(conn, self).await.map(Spec::AppStore),
       Whois => specs::Whois::for_check(conn, self).await.map(Spec::Whois),
       #[cfg(feature = "python")]
       Python => specs::Python::for_check(conn, self).await.map(Spec::Python),
       DeadManSwitch => specs::DeadManSwitch::for_check(conn, self).await.map(Spec::DeadManSwitch),
+      External => specs::External::for_check(conn, self).await.map(Spec::External),
       Unsupported => Ok(Spec::Unsupported),
     }
   }
@@ -363,6 +381,54 @@ impl Check {
       Tcp => TcpHandler { check: self }.check(conn, config, site, stash).await,
       Udp => UdpHandler { check: self }.check(conn, config, site, stash).await,
       Tls => TlsHandler { check: self }.check(conn, config, site, stash).await,
+      Smtp => SmtpHandler { check: self }.check(conn, config, site, stash).await,
+      MailAccess => MailAccessHandler { check: self }.check(conn, config, site, stash).await,
+      Ssh => SshHandler { check: self }.check(conn, config, site, stash).await,
+      Grpc => GrpcHandler { check: self }.check(conn, config, site, stash).await,
+      WebSocket => WebSocketHandler { check: self }.check(conn, config, site, stash).await,
+      Database => DatabaseHandler { check: self }.check(conn, config, site, stash).await,
+      Ntp => NtpHandler { check: self }.check(conn, config, site, stash).await,
+
+      DnsConsistency => {
+        DnsConsistencyHandler {
+          check: self,
+          resolver: config.checks.dns_resolver,
+        }
+        .check(conn, config, site, stash)
+        .await
+      }
+
+      Dnssec => {
+        DnssecHandler {
+          check: self,
+          resolver: config.checks.dns_resolver,
+        }
+        .check(conn, config, site, stash)
+        .await
+      }
+
+      HttpFlow => HttpFlowHandler { check: self }.check(conn, config, site, stash).await,
+      SecurityHeaders => SecurityHeadersHandler { check: self }.check(conn, config, site, stash).await,
+      Crawl => CrawlHandler { check: self }.check(conn, config, site, stash).await,
+      Prometheus => PrometheusHandler { check: self }.check(conn, config, site, stash).await,
+      Exec => {
+        ExecHandler {
+          check: self,
+          path: config.checks.plugins_path.clone(),
+        }
+        .check(conn, config, site, stash)
+        .await
+      }
+      Script => ScriptHandler { check: self }.check(conn, config, site, stash).await,
+      #[cfg(feature = "wasm")]
+      Wasm => {
+        WasmHandler {
+          check: self,
+          path: config.checks.plugins_path.clone(),
+        }
+        .check(conn, config, site, stash)
+        .await
+      }
       PlayStore => PlayStoreHandler { check: self }.check(conn, config, site, stash).await,
       AppStore => AppStoreHandler { check: self }.check(conn, config, site, stash).await,
       Whois => WhoisHandler { check: self }.check(conn, config, site, stash).await,
@@ -380,6 +446,14 @@ impl Check {
 
         DeadManSwitchHandler { check: self, last }.check(conn, config, site, stash).await
       }
+      External => {
+        ExternalHandler {
+          check: self,
+          path: config.checks.handlers_path.clone(),
+        }
+        .check(conn, config, site, stash)
+        .await
+      }
       Unsupported => Err(anyhow!("unsupported check kind")),
     }
   }
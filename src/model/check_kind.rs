@@ -20,6 +20,23 @@ pub const KINDS: &[&str] = &[
   "tcp",
   "udp",
   "tls",
+  "smtp",
+  "mail_access",
+  "ssh",
+  "grpc",
+  "websocket",
+  "database",
+  "ntp",
+  "dns_consistency",
+  "dnssec",
+  "http_flow",
+  "security_headers",
+  "crawl",
+  "prometheus",
+  "exec",
+  "script",
+  #[cfg(feature = "wasm")]
+  "wasm",
   "play_store",
   "app_store",
   "domain",
@@ -37,12 +54,30 @@ pub enum CheckKind {
   Tcp,
   Udp,
   Tls,
+  Smtp,
+  MailAccess,
+  Ssh,
+  Grpc,
+  WebSocket,
+  Database,
+  Ntp,
+  DnsConsistency,
+  Dnssec,
+  HttpFlow,
+  SecurityHeaders,
+  Crawl,
+  Prometheus,
+  Exec,
+  Script,
+  #[cfg(feature = "wasm")]
+  Wasm,
   PlayStore,
   AppStore,
   Whois,
   #[cfg(feature = "python")]
   Python,
   DeadManSwitch,
+  External,
   #[default]
   Unsupported,
 }
@@ -59,12 +94,30 @@ impl Display for CheckKind {
       Tcp => "tcp",
       Udp => "udp",
       Tls => "tls",
+      Smtp => "smtp",
+      MailAccess => "mail_access",
+      Ssh => "ssh",
+      Grpc => "grpc",
+      WebSocket => "websocket",
+      Database => "database",
+      Ntp => "ntp",
+      DnsConsistency => "dns_consistency",
+      Dnssec => "dnssec",
+      HttpFlow => "http_flow",
+      SecurityHeaders => "security_headers",
+      Crawl => "crawl",
+      Prometheus => "prometheus",
+      Exec => "exec",
+      Script => "script",
+      #[cfg(feature = "wasm")]
+      Wasm => "wasm",
       PlayStore => "play_store",
       AppStore => "app_store",
       Whois => "domain",
       #[cfg(feature = "python")]
       Python => "python",
       DeadManSwitch => "deadmanswitch",
+      External => "external",
       Unsupported => "unsupported",
     };
 
@@ -89,12 +142,30 @@ impl TryFrom<String> for CheckKind {
       "tcp" => Ok(Tcp),
       "udp" => Ok(Udp),
       "tls" => Ok(Tls),
+      "smtp" => Ok(Smtp),
+      "mail_access" => Ok(MailAccess),
+      "ssh" => Ok(Ssh),
+      "grpc" => Ok(Grpc),
+      "websocket" => Ok(WebSocket),
+      "database" => Ok(Database),
+      "ntp" => Ok(Ntp),
+      "dns_consistency" => Ok(DnsConsistency),
+      "dnssec" => Ok(Dnssec),
+      "http_flow" => Ok(HttpFlow),
+      "security_headers" => Ok(SecurityHeaders),
+      "crawl" => Ok(Crawl),
+      "prometheus" => Ok(Prometheus),
+      "exec" => Ok(Exec),
+      "script" => Ok(Script),
+      #[cfg(feature = "wasm")]
+      "wasm" => Ok(Wasm),
       "play_store" => Ok(PlayStore),
       "app_store" => Ok(AppStore),
       "domain" => Ok(Whois),
       #[cfg(feature = "python")]
       "python" => Ok(Python),
       "deadmanswitch" => Ok(DeadManSwitch),
+      "external" => Ok(External),
       _ => Err(anyhow!("invalid value for kind")),
     }
   }
@@ -11,6 +11,7 @@ pub mod status {
   pub const OK: u8 = 0;
   pub const CRITICAL: u8 = 1;
   pub const WARNING: u8 = 2;
+  pub const UNKNOWN: u8 = 3;
 }
 
 #[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
@@ -0,0 +1,12 @@
+CREATE TABLE smtp_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `host` VARCHAR(255) NOT NULL,
+  `port` SMALLINT UNSIGNED NOT NULL,
+  `starttls` TINYINT(1) NOT NULL DEFAULT 0,
+  `extensions` TEXT NOT NULL,
+  `auth_mechanisms` TEXT NOT NULL,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_smtp_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
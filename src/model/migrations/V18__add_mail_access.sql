@@ -0,0 +1,16 @@
+CREATE TABLE mail_access_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `protocol` VARCHAR(255) NOT NULL,
+  `host` VARCHAR(255) NOT NULL,
+  `port` SMALLINT UNSIGNED NOT NULL,
+  `security` VARCHAR(255) NOT NULL,
+  `username` VARCHAR(255),
+  `password` VARCHAR(255),
+  `mailbox` VARCHAR(255),
+  `min_messages` INT UNSIGNED,
+  `max_messages` INT UNSIGNED,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_mail_access_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+CREATE TABLE ssh_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `host` VARCHAR(255) NOT NULL,
+  `port` SMALLINT UNSIGNED NOT NULL,
+  `algorithm` VARCHAR(255),
+  `fingerprint` VARCHAR(255),
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_ssh_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
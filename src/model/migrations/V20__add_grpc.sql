@@ -0,0 +1,10 @@
+CREATE TABLE grpc_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `target` VARCHAR(255) NOT NULL,
+  `service` VARCHAR(255),
+  `tls` TINYINT(1) NOT NULL DEFAULT 0,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_grpc_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+CREATE TABLE websocket_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `url` VARCHAR(255) NOT NULL,
+  `headers` TEXT,
+  `message` TEXT,
+  `content` TEXT,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_websocket_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+CREATE TABLE database_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `engine` VARCHAR(16) NOT NULL,
+  `dsn` TEXT NOT NULL,
+  `query` TEXT,
+  `expected` TEXT,
+  `max_latency` BIGINT UNSIGNED,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_database_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
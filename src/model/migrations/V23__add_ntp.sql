@@ -0,0 +1,11 @@
+CREATE TABLE ntp_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `host` VARCHAR(255) NOT NULL,
+  `port` SMALLINT UNSIGNED NOT NULL DEFAULT 123,
+  `warning_ms` INT UNSIGNED,
+  `critical_ms` INT UNSIGNED,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_ntp_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
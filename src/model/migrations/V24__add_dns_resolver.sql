@@ -0,0 +1,12 @@
+ALTER TABLE dns_specs
+ADD COLUMN `resolver` VARCHAR(255),
+ADD COLUMN `port` SMALLINT UNSIGNED,
+ADD COLUMN `transport` VARCHAR(255) NOT NULL DEFAULT 'udp',
+ADD COLUMN `values` TEXT,
+ADD COLUMN `matching` VARCHAR(255) NOT NULL DEFAULT 'any',
+ADD COLUMN `max_ttl` INT UNSIGNED;
+
+UPDATE dns_specs SET `values` = '[]';
+
+ALTER TABLE dns_specs
+MODIFY COLUMN `values` TEXT NOT NULL;
@@ -0,0 +1,10 @@
+CREATE TABLE dns_consistency_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `zone` VARCHAR(255) NOT NULL,
+  `record` VARCHAR(255) NOT NULL,
+  `domain` VARCHAR(255),
+  `nameservers` TEXT NOT NULL,
+
+  CONSTRAINT fk_dns_consistency_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
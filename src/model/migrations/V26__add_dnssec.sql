@@ -0,0 +1,9 @@
+CREATE TABLE dnssec_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `domain` VARCHAR(255) NOT NULL,
+  `record` VARCHAR(255) NOT NULL,
+  `window` BIGINT UNSIGNED NOT NULL,
+
+  CONSTRAINT fk_dnssec_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
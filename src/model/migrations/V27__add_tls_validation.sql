@@ -0,0 +1,9 @@
+ALTER TABLE `tls_specs`
+ADD COLUMN `port` SMALLINT UNSIGNED NOT NULL DEFAULT 443,
+ADD COLUMN `verify_hostname` TINYINT(1) NOT NULL DEFAULT 0,
+ADD COLUMN `verify_chain` TINYINT(1) NOT NULL DEFAULT 0,
+ADD COLUMN `issuer` VARCHAR(255),
+ADD COLUMN `min_key_size` INT UNSIGNED,
+ADD COLUMN `signature_algorithm` VARCHAR(255),
+ADD COLUMN `ocsp` TINYINT(1) NOT NULL DEFAULT 0,
+ADD COLUMN `timeout` BIGINT UNSIGNED;
@@ -0,0 +1,2 @@
+ALTER TABLE `tls_specs`
+ADD COLUMN `starttls` VARCHAR(255) AFTER `ocsp`;
@@ -0,0 +1,3 @@
+ALTER TABLE `tls_specs`
+ADD COLUMN `fingerprint` VARCHAR(255) AFTER `ocsp`,
+ADD COLUMN `learn` TINYINT(1) NOT NULL DEFAULT 0 AFTER `fingerprint`;
@@ -0,0 +1,11 @@
+ALTER TABLE `http_specs`
+ADD COLUMN `method` VARCHAR(16) NOT NULL DEFAULT 'GET' AFTER `url`,
+ADD COLUMN `body` TEXT AFTER `headers`,
+ADD COLUMN `username` VARCHAR(255) AFTER `body`,
+ADD COLUMN `password` VARCHAR(255) AFTER `username`,
+ADD COLUMN `token` TEXT AFTER `password`,
+ADD COLUMN `client_cert` TEXT AFTER `token`,
+ADD COLUMN `client_key` TEXT AFTER `client_cert`,
+ADD COLUMN `proxy` VARCHAR(255) AFTER `client_key`,
+ADD COLUMN `follow_redirects` TINYINT(1) NOT NULL DEFAULT 1 AFTER `proxy`,
+ADD COLUMN `verify_tls` TINYINT(1) NOT NULL DEFAULT 1 AFTER `follow_redirects`;
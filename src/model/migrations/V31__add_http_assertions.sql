@@ -0,0 +1,13 @@
+ALTER TABLE `http_specs`
+ADD COLUMN `codes` TEXT AFTER `code`,
+ADD COLUMN `response_headers` TEXT AFTER `codes`,
+ADD COLUMN `regex` TEXT AFTER `content`,
+ADD COLUMN `not_content` TEXT AFTER `regex`,
+ADD COLUMN `not_regex` TEXT AFTER `not_content`,
+ADD COLUMN `json_schema` TEXT AFTER `json_query`;
+
+UPDATE `http_specs` SET `codes` = '[]', `response_headers` = '{}';
+
+ALTER TABLE `http_specs`
+MODIFY COLUMN `codes` TEXT NOT NULL,
+MODIFY COLUMN `response_headers` TEXT NOT NULL;
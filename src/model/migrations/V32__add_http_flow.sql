@@ -0,0 +1,7 @@
+CREATE TABLE http_flow_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `steps` TEXT NOT NULL,
+
+  CONSTRAINT fk_http_flow_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+ALTER TABLE `http_specs`
+ADD COLUMN `max_hops` TINYINT UNSIGNED AFTER `verify_tls`,
+ADD COLUMN `final_url` TEXT AFTER `max_hops`,
+ADD COLUMN `final_host` VARCHAR(255) AFTER `final_url`,
+ADD COLUMN `no_downgrade` TINYINT(1) NOT NULL DEFAULT 0 AFTER `final_host`;
@@ -0,0 +1,10 @@
+CREATE TABLE security_headers_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `url` TEXT NOT NULL,
+  `hsts_max_age` BIGINT UNSIGNED,
+  `mandatory` TEXT NOT NULL,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_security_headers_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
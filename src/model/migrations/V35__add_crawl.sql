@@ -0,0 +1,10 @@
+CREATE TABLE crawl_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `url` TEXT NOT NULL,
+  `max_depth` TINYINT UNSIGNED,
+  `max_urls` SMALLINT UNSIGNED,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_crawl_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+CREATE TABLE prometheus_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `url` TEXT NOT NULL,
+  `metric` VARCHAR(255) NOT NULL,
+  `labels` TEXT NOT NULL,
+  `warning` VARCHAR(255),
+  `critical` VARCHAR(255),
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_prometheus_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+CREATE TABLE exec_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `command` VARCHAR(255) NOT NULL,
+  `arguments` TEXT NOT NULL,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_exec_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
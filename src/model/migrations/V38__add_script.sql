@@ -0,0 +1,8 @@
+CREATE TABLE script_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `code` MEDIUMTEXT NOT NULL,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_script_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+CREATE TABLE wasm_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `plugin` VARCHAR(255),
+  `module` MEDIUMBLOB,
+  `config` TEXT,
+  `fuel` BIGINT UNSIGNED,
+  `memory` INT UNSIGNED,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_wasm_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
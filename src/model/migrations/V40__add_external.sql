@@ -0,0 +1,9 @@
+CREATE TABLE external_specs (
+  `id` BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  `check_id` BIGINT UNSIGNED NOT NULL,
+  `handler` VARCHAR(255) NOT NULL,
+  `spec` TEXT,
+  `timeout` BIGINT UNSIGNED,
+
+  CONSTRAINT fk_external_check FOREIGN KEY (check_id) REFERENCES checks (id) ON DELETE CASCADE
+);
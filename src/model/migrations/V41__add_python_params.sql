@@ -0,0 +1,5 @@
+ALTER TABLE python_specs
+ADD params TEXT,
+ADD timeout BIGINT UNSIGNED;
+
+UPDATE python_specs SET params = "{}" WHERE params IS NULL;
@@ -12,6 +12,7 @@ mod group;
 mod outage;
 mod site;
 mod site_outage;
+mod string_list;
 mod timeline;
 mod user;
 
@@ -30,6 +31,7 @@ pub use self::{
   outage::Outage,
   site::Site,
   site_outage::SiteOutage,
+  string_list::StringList,
   timeline::Timeline,
   user::User,
 };
@@ -6,7 +6,7 @@ use uuid::Uuid;
 
 use crate::{
   api::error::Shortable,
-  model::{Check, Event},
+  model::{status::*, Check, Event},
 };
 
 #[derive(Debug)]
@@ -136,7 +136,7 @@ impl SiteOutage {
 
     let outage = match outage {
       Ok(OutageRef::Existing(outage)) => {
-        if outage.failing_strikes < check.failing_threshold && event.status == 1 {
+        if outage.failing_strikes < check.failing_threshold && event.status == CRITICAL {
           sqlx::query(
             "
               UPDATE site_outages
@@ -160,7 +160,7 @@ impl SiteOutage {
           }
         }
 
-        if outage.passing_strikes < check.passing_threshold && event.status == 0 {
+        if outage.passing_strikes < check.passing_threshold && event.status == OK {
           let ended_on = if outage.passing_strikes + 1 == check.passing_threshold {
             kvlog!(Info, "site outage resolved", {
               "site" => event.site,
@@ -195,7 +195,7 @@ impl SiteOutage {
       }
 
       Ok(OutageRef::New) => {
-        if event.status != 0 {
+        if event.status == CRITICAL {
           let uuid = Uuid::new_v4().to_string();
 
           sqlx::query(
@@ -304,7 +304,7 @@ mod tests {
 
   use crate::{
     config::CONTROLLER_ID,
-    model::{Check, Event, SiteOutage},
+    model::{status::*, Check, Event, SiteOutage},
     tests,
   };
 
@@ -455,6 +455,42 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  async fn insert_non_critical() -> Result<()> {
+    let pool = tests::db_client().await?;
+
+    {
+      let mut conn = pool.acquire().await?;
+
+      pool.create_check(None, None, "insert_non_critical()", None, None).await?;
+
+      let check = Check {
+        id: 1,
+        failing_threshold: 1,
+        passing_threshold: 1,
+        ..Default::default()
+      };
+
+      for status in [WARNING, UNKNOWN] {
+        let event = Event {
+          check_id: 1,
+          status,
+          message: "inconclusive".to_string(),
+          ..Default::default()
+        };
+
+        assert!(SiteOutage::insert(&mut conn, &check, &event).await?.is_none());
+      }
+
+      let outages = sqlx::query_as::<_, (i64,)>("SELECT COUNT(id) FROM site_outages").fetch_one(&*pool).await?;
+      assert_eq!(outages, (0,));
+    }
+
+    pool.cleanup().await;
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn current() -> Result<()> {
     let pool = tests::db_client().await?;
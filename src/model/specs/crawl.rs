@@ -0,0 +1,83 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration};
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct Crawl {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub url: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_depth: Option<u8>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_urls: Option<u16>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Crawl {
+  fn name(&self) -> &'static str {
+    "Crawl"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("URL", self.url.clone())]
+  }
+}
+
+impl Crawl {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Crawl> {
+    let spec = sqlx::query_as::<_, Crawl>(
+      "
+        SELECT id, check_id, url, max_depth, max_urls, timeout
+        FROM crawl_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Crawl) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO crawl_specs ( check_id, url, max_depth, max_urls, timeout )
+        VALUES ( ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.url)
+    .bind(spec.max_depth)
+    .bind(spec.max_urls)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Crawl) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE crawl_specs
+        SET url = ?, max_depth = ?, max_urls = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.url)
+    .bind(spec.max_depth)
+    .bind(spec.max_urls)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,72 @@
+use std::{
+  convert::TryFrom,
+  error::Error,
+  fmt::{self, Display, Formatter},
+};
+
+use sqlx::{
+  encode::IsNull,
+  error::BoxDynError,
+  mysql::{MySqlTypeInfo, MySqlValueRef},
+  types::Type,
+  Decode, Encode, MySql,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseEngine {
+  #[default]
+  Mysql,
+  Postgres,
+  Redis,
+}
+
+impl Display for DatabaseEngine {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+    use DatabaseEngine::*;
+
+    let name = match self {
+      Mysql => "mysql",
+      Postgres => "postgres",
+      Redis => "redis",
+    };
+
+    write!(formatter, "{name}")
+  }
+}
+
+impl TryFrom<String> for DatabaseEngine {
+  type Error = anyhow::Error;
+
+  fn try_from(engine: String) -> Result<DatabaseEngine, Self::Error> {
+    use DatabaseEngine::*;
+
+    match engine.as_str() {
+      "mysql" => Ok(Mysql),
+      "postgres" => Ok(Postgres),
+      "redis" => Ok(Redis),
+      _ => Err(anyhow!("invalid value for engine")),
+    }
+  }
+}
+
+impl Type<MySql> for DatabaseEngine {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for DatabaseEngine {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(self.to_string(), buf)
+  }
+}
+
+impl Decode<'_, MySql> for DatabaseEngine {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(DatabaseEngine::try_from(<&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)?)?)
+  }
+}
@@ -0,0 +1,4 @@
+mod engine;
+mod spec;
+
+pub use self::{engine::*, spec::*};
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{
+  specs::{DatabaseEngine, SpecMeta},
+  Check, Duration,
+};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct Database {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub engine: DatabaseEngine,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub dsn: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub query: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub expected: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_latency: Option<Duration>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Database {
+  fn name(&self) -> &'static str {
+    "Database"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("Engine", self.engine.to_string())];
+
+    if let Some(ref query) = self.query {
+      fields.push(("Query", query.clone()));
+    }
+
+    fields
+  }
+}
+
+impl Database {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Database> {
+    let spec = sqlx::query_as::<_, Database>(
+      "
+        SELECT id, check_id, engine, dsn, query, expected, max_latency, timeout
+        FROM database_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Database) -> Result<()> {
+    let dsn = spec.dsn.ok_or_else(|| anyhow!("`dsn` is required"))?;
+
+    sqlx::query(
+      "
+        INSERT INTO database_specs ( check_id, engine, dsn, query, expected, max_latency, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.engine)
+    .bind(dsn)
+    .bind(spec.query)
+    .bind(spec.expected)
+    .bind(spec.max_latency)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  // The DSN is never returned by the API, so an update without one keeps the
+  // stored value.
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Database) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE database_specs
+        SET engine = ?, dsn = COALESCE(?, dsn), query = ?, expected = ?, max_latency = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.engine)
+    .bind(spec.dsn)
+    .bind(spec.query)
+    .bind(spec.expected)
+    .bind(spec.max_latency)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
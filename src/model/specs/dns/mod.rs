@@ -1,4 +1,5 @@
 mod record;
 mod spec;
+mod transport;
 
-pub use self::spec::*;
+pub use self::{spec::*, transport::*};
@@ -1,11 +1,14 @@
 use anyhow::Result;
 use sqlx::{FromRow, MySqlConnection};
 
-use crate::model::{specs::SpecMeta, Check};
+use crate::model::{
+  specs::{DnsMatching, DnsTransport, SpecMeta},
+  Check, StringList,
+};
 
 pub use super::record::*;
 
-#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
 pub struct Dns {
   #[serde(skip)]
   pub id: u64,
@@ -14,7 +17,22 @@ pub struct Dns {
   #[serde(default)]
   pub record: DnsRecord,
   pub domain: String,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "String::is_empty")]
   pub value: String,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub values: StringList,
+  #[serde(default)]
+  pub matching: DnsMatching,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub resolver: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub port: Option<u16>,
+  #[serde(default)]
+  pub transport: DnsTransport,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_ttl: Option<u32>,
 }
 
 impl SpecMeta for Dns {
@@ -23,7 +41,13 @@ impl SpecMeta for Dns {
   }
 
   fn fields(&self) -> Vec<(&'static str, String)> {
-    vec![("Record type", self.record.to_string()), ("Domain", self.domain.clone())]
+    let mut fields = vec![("Record type", self.record.to_string()), ("Domain", self.domain.clone())];
+
+    if let Some(ref resolver) = self.resolver {
+      fields.push(("Resolver", format!("{resolver} ({})", self.transport)));
+    }
+
+    fields
   }
 }
 
@@ -31,7 +55,7 @@ impl Dns {
   pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Dns> {
     let spec = sqlx::query_as::<_, Dns>(
       "
-        SELECT id, check_id, record, domain, value
+        SELECT id, check_id, record, domain, value, `values`, matching, resolver, port, transport, max_ttl
         FROM dns_specs
         WHERE check_id = ?
       ",
@@ -46,14 +70,20 @@ impl Dns {
   pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Dns) -> Result<()> {
     sqlx::query(
       "
-        INSERT INTO dns_specs ( check_id, record, domain, value )
-        VALUES ( ?, ?, ?, ? )
+        INSERT INTO dns_specs ( check_id, record, domain, value, `values`, matching, resolver, port, transport, max_ttl )
+        VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, ?, ? )
       ",
     )
     .bind(check.id)
     .bind(spec.record)
     .bind(spec.domain)
     .bind(spec.value)
+    .bind(spec.values)
+    .bind(spec.matching)
+    .bind(spec.resolver)
+    .bind(spec.port)
+    .bind(spec.transport)
+    .bind(spec.max_ttl)
     .execute(pool)
     .await?;
 
@@ -64,13 +94,19 @@ impl Dns {
     sqlx::query(
       "
         UPDATE dns_specs
-        SET record = ?, domain = ?, value = ?
+        SET record = ?, domain = ?, value = ?, `values` = ?, matching = ?, resolver = ?, port = ?, transport = ?, max_ttl = ?
         WHERE check_id = ?
       ",
     )
     .bind(spec.record)
     .bind(spec.domain)
     .bind(spec.value)
+    .bind(spec.values)
+    .bind(spec.matching)
+    .bind(spec.resolver)
+    .bind(spec.port)
+    .bind(spec.transport)
+    .bind(spec.max_ttl)
     .bind(check.id)
     .execute(conn)
     .await?;
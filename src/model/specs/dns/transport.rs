@@ -0,0 +1,131 @@
+use std::{
+  convert::TryFrom,
+  error::Error,
+  fmt::{self, Display, Formatter},
+};
+
+use sqlx::{
+  encode::IsNull,
+  error::BoxDynError,
+  mysql::{MySqlTypeInfo, MySqlValueRef},
+  types::Type,
+  Decode, Encode, MySql,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DnsTransport {
+  #[default]
+  Udp,
+  Tcp,
+  Dot,
+  Doh,
+}
+
+impl Display for DnsTransport {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+    use DnsTransport::*;
+
+    let name = match self {
+      Udp => "udp",
+      Tcp => "tcp",
+      Dot => "dot",
+      Doh => "doh",
+    };
+
+    write!(formatter, "{name}")
+  }
+}
+
+impl TryFrom<String> for DnsTransport {
+  type Error = anyhow::Error;
+
+  fn try_from(transport: String) -> Result<DnsTransport, Self::Error> {
+    use DnsTransport::*;
+
+    match transport.as_str() {
+      "udp" => Ok(Udp),
+      "tcp" => Ok(Tcp),
+      "dot" => Ok(Dot),
+      "doh" => Ok(Doh),
+      _ => Err(anyhow!("invalid value for transport")),
+    }
+  }
+}
+
+impl Type<MySql> for DnsTransport {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for DnsTransport {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(self.to_string(), buf)
+  }
+}
+
+impl Decode<'_, MySql> for DnsTransport {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(DnsTransport::try_from(<&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)?)?)
+  }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DnsMatching {
+  #[default]
+  Any,
+  All,
+}
+
+impl Display for DnsMatching {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+    use DnsMatching::*;
+
+    let name = match self {
+      Any => "any",
+      All => "all",
+    };
+
+    write!(formatter, "{name}")
+  }
+}
+
+impl TryFrom<String> for DnsMatching {
+  type Error = anyhow::Error;
+
+  fn try_from(matching: String) -> Result<DnsMatching, Self::Error> {
+    use DnsMatching::*;
+
+    match matching.as_str() {
+      "any" => Ok(Any),
+      "all" => Ok(All),
+      _ => Err(anyhow!("invalid value for matching")),
+    }
+  }
+}
+
+impl Type<MySql> for DnsMatching {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for DnsMatching {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(self.to_string(), buf)
+  }
+}
+
+impl Decode<'_, MySql> for DnsMatching {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(DnsMatching::try_from(<&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)?)?)
+  }
+}
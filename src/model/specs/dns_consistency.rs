@@ -0,0 +1,91 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{
+  specs::{DnsRecord, SpecMeta},
+  Check, StringList,
+};
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct DnsConsistency {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub zone: String,
+  #[serde(default)]
+  pub record: DnsRecord,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub domain: Option<String>,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub nameservers: StringList,
+}
+
+impl SpecMeta for DnsConsistency {
+  fn name(&self) -> &'static str {
+    "DNS consistency"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![
+      ("Zone", self.zone.clone()),
+      ("Record type", self.record.to_string()),
+      ("Domain", self.domain.clone().unwrap_or_else(|| self.zone.clone())),
+    ]
+  }
+}
+
+impl DnsConsistency {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<DnsConsistency> {
+    let spec = sqlx::query_as::<_, DnsConsistency>(
+      "
+        SELECT id, check_id, zone, record, domain, nameservers
+        FROM dns_consistency_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: DnsConsistency) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO dns_consistency_specs ( check_id, zone, record, domain, nameservers )
+        VALUES ( ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.zone)
+    .bind(spec.record)
+    .bind(spec.domain)
+    .bind(spec.nameservers)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: DnsConsistency) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE dns_consistency_specs
+        SET zone = ?, record = ?, domain = ?, nameservers = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.zone)
+    .bind(spec.record)
+    .bind(spec.domain)
+    .bind(spec.nameservers)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
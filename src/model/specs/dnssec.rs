@@ -1,34 +1,39 @@
 use anyhow::Result;
 use sqlx::{FromRow, MySqlConnection};
 
-use crate::model::{specs::SpecMeta, Check, Duration};
+use crate::model::{
+  specs::{DnsRecord, SpecMeta},
+  Check, Duration,
+};
 
 #[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
-pub struct Tls {
+pub struct Dnssec {
   #[serde(skip)]
   pub id: u64,
   #[serde(skip)]
   pub check_id: u64,
   pub domain: String,
+  #[serde(default)]
+  pub record: DnsRecord,
   pub window: Duration,
 }
 
-impl SpecMeta for Tls {
+impl SpecMeta for Dnssec {
   fn name(&self) -> &'static str {
-    "TLS certificate expiration"
+    "DNSSEC validation"
   }
 
   fn fields(&self) -> Vec<(&'static str, String)> {
-    vec![("Domain", self.domain.clone())]
+    vec![("Domain", self.domain.clone()), ("Record type", self.record.to_string())]
   }
 }
 
-impl Tls {
-  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Tls> {
-    let spec = sqlx::query_as::<_, Tls>(
+impl Dnssec {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Dnssec> {
+    let spec = sqlx::query_as::<_, Dnssec>(
       "
-        SELECT id, check_id, domain, window
-        FROM tls_specs
+        SELECT id, check_id, domain, record, window
+        FROM dnssec_specs
         WHERE check_id = ?
       ",
     )
@@ -39,15 +44,16 @@ impl Tls {
     Ok(spec)
   }
 
-  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Tls) -> Result<()> {
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Dnssec) -> Result<()> {
     sqlx::query(
       "
-        INSERT INTO tls_specs ( check_id, domain, window )
-        VALUES ( ?, ?, ? )
+        INSERT INTO dnssec_specs ( check_id, domain, record, window )
+        VALUES ( ?, ?, ?, ? )
       ",
     )
     .bind(check.id)
     .bind(spec.domain)
+    .bind(spec.record)
     .bind(spec.window)
     .execute(pool)
     .await?;
@@ -55,15 +61,16 @@ impl Tls {
     Ok(())
   }
 
-  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Tls) -> Result<()> {
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Dnssec) -> Result<()> {
     sqlx::query(
       "
-        UPDATE tls_specs
-        SET domain = ?, window = ?
+        UPDATE dnssec_specs
+        SET domain = ?, record = ?, window = ?
         WHERE check_id = ?
       ",
     )
     .bind(spec.domain)
+    .bind(spec.record)
     .bind(spec.window)
     .bind(check.id)
     .execute(conn)
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration, StringList};
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct Exec {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub command: String,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub arguments: StringList,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Exec {
+  fn name(&self) -> &'static str {
+    "Nagios plugin"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("Command", self.command.clone()), ("Arguments", self.arguments.join(" "))]
+  }
+}
+
+impl Exec {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Exec> {
+    let spec = sqlx::query_as::<_, Exec>(
+      "
+        SELECT id, check_id, command, arguments, timeout
+        FROM exec_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Exec) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO exec_specs ( check_id, command, arguments, timeout )
+        VALUES ( ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.command)
+    .bind(spec.arguments)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Exec) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE exec_specs
+        SET command = ?, arguments = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.command)
+    .bind(spec.arguments)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use serde_json::{Map, Value};
+use sqlx::{types::Json, FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration};
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct External {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  #[serde(rename = "kind")]
+  pub handler: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+  #[serde(flatten)]
+  pub spec: Json<Map<String, Value>>,
+}
+
+impl SpecMeta for External {
+  fn name(&self) -> &'static str {
+    "External handler"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("Handler", self.handler.clone())]
+  }
+}
+
+impl External {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<External> {
+    let spec = sqlx::query_as::<_, External>(
+      "
+        SELECT id, check_id, handler, spec, timeout
+        FROM external_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: External) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO external_specs ( check_id, handler, spec, timeout )
+        VALUES ( ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.handler)
+    .bind(spec.spec)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: External) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE external_specs
+        SET spec = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.spec)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
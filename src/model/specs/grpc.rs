@@ -0,0 +1,92 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::{
+  ext,
+  model::{specs::SpecMeta, Check, Duration},
+};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct Grpc {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub target: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub service: Option<String>,
+  #[serde(default = "ext::to_false")]
+  pub tls: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Grpc {
+  fn name(&self) -> &'static str {
+    "gRPC health check"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("Target", self.target.clone())];
+
+    if let Some(ref service) = self.service {
+      fields.push(("Service", service.clone()));
+    }
+
+    fields
+  }
+}
+
+impl Grpc {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Grpc> {
+    let spec = sqlx::query_as::<_, Grpc>(
+      "
+        SELECT id, check_id, target, service, tls, timeout
+        FROM grpc_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Grpc) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO grpc_specs ( check_id, target, service, tls, timeout )
+        VALUES ( ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.target)
+    .bind(spec.service)
+    .bind(spec.tls)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Grpc) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE grpc_specs
+        SET target = ?, service = ?, tls = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.target)
+    .bind(spec.service)
+    .bind(spec.tls)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
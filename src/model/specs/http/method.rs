@@ -0,0 +1,84 @@
+use std::{
+  convert::TryFrom,
+  error::Error,
+  fmt::{self, Display, Formatter},
+};
+
+use sqlx::{
+  encode::IsNull,
+  error::BoxDynError,
+  mysql::{MySqlTypeInfo, MySqlValueRef},
+  types::Type,
+  Decode, Encode, MySql,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+  #[default]
+  Get,
+  Head,
+  Post,
+  Put,
+  Patch,
+  Delete,
+  Options,
+}
+
+impl Display for HttpMethod {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+    use HttpMethod::*;
+
+    let name = match self {
+      Get => "GET",
+      Head => "HEAD",
+      Post => "POST",
+      Put => "PUT",
+      Patch => "PATCH",
+      Delete => "DELETE",
+      Options => "OPTIONS",
+    };
+
+    write!(formatter, "{name}")
+  }
+}
+
+impl TryFrom<String> for HttpMethod {
+  type Error = anyhow::Error;
+
+  fn try_from(method: String) -> Result<HttpMethod, Self::Error> {
+    use HttpMethod::*;
+
+    match method.to_uppercase().as_str() {
+      "GET" => Ok(Get),
+      "HEAD" => Ok(Head),
+      "POST" => Ok(Post),
+      "PUT" => Ok(Put),
+      "PATCH" => Ok(Patch),
+      "DELETE" => Ok(Delete),
+      "OPTIONS" => Ok(Options),
+      _ => Err(anyhow!("invalid value for method")),
+    }
+  }
+}
+
+impl Type<MySql> for HttpMethod {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for HttpMethod {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(self.to_string(), buf)
+  }
+}
+
+impl Decode<'_, MySql> for HttpMethod {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(HttpMethod::try_from(<&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)?)?)
+  }
+}
@@ -1,4 +1,5 @@
 mod headers;
+mod method;
 mod spec;
 
-pub use self::{headers::*, spec::*};
+pub use self::{headers::*, method::*, spec::*};
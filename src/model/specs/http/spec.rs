@@ -1,11 +1,17 @@
 use std::collections::HashMap;
 
 use anyhow::Result;
-use sqlx::{FromRow, MySqlConnection};
+use sqlx::{types::Json, FromRow, MySqlConnection};
 
-use crate::model::{
-  specs::{http::HttpHeaders, SpecMeta},
-  Check, Duration,
+use crate::{
+  ext,
+  model::{
+    specs::{
+      http::{HttpHeaders, HttpMethod},
+      SpecMeta,
+    },
+    Check, Duration, StringList,
+  },
 };
 
 #[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
@@ -16,37 +22,130 @@ pub struct Http {
   pub check_id: u64,
   pub url: String,
   #[serde(default)]
+  pub method: HttpMethod,
+  #[serde(default)]
   #[serde(skip_serializing_if = "HashMap::is_empty")]
   pub headers: HttpHeaders,
   #[serde(skip_serializing_if = "Option::is_none")]
+  pub body: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub username: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub password: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub token: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub client_cert: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub client_key: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub proxy: Option<String>,
+  #[serde(default = "ext::to_true")]
+  pub follow_redirects: bool,
+  #[serde(default = "ext::to_true")]
+  pub verify_tls: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_hops: Option<u8>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub final_url: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub final_host: Option<String>,
+  #[serde(default = "ext::to_false")]
+  pub no_downgrade: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub timeout: Option<Duration>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub code: Option<u16>,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub codes: StringList,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  pub response_headers: HttpHeaders,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub content: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
+  pub regex: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub not_content: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub not_regex: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub digest: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub json_query: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
+  pub json_schema: Option<Json<serde_json::Value>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub duration: Option<Duration>,
 }
 
+impl Default for Http {
+  fn default() -> Http {
+    Http {
+      id: 0,
+      check_id: 0,
+      url: String::new(),
+      method: HttpMethod::default(),
+      headers: HttpHeaders::default(),
+      body: None,
+      username: None,
+      password: None,
+      token: None,
+      client_cert: None,
+      client_key: None,
+      proxy: None,
+      follow_redirects: true,
+      verify_tls: true,
+      max_hops: None,
+      final_url: None,
+      final_host: None,
+      no_downgrade: false,
+      timeout: None,
+      code: None,
+      codes: StringList::default(),
+      response_headers: HttpHeaders::default(),
+      content: None,
+      regex: None,
+      not_content: None,
+      not_regex: None,
+      digest: None,
+      json_query: None,
+      json_schema: None,
+      duration: None,
+    }
+  }
+}
+
 impl SpecMeta for Http {
   fn name(&self) -> &'static str {
     "HTTP request"
   }
 
   fn fields(&self) -> Vec<(&'static str, String)> {
-    vec![("URL", self.url.clone())]
+    vec![("Method", self.method.to_string()), ("URL", self.url.clone())]
   }
 }
 
 impl Http {
+  /// Whether redirects are followed one by one to assert on the whole chain.
+  pub fn tracks_redirects(&self) -> bool {
+    self.max_hops.is_some() || self.final_url.is_some() || self.final_host.is_some() || self.no_downgrade
+  }
+
+  /// Reject redirect assertions on a spec that does not follow redirects, since they could never be honoured.
+  pub fn validate(&self) -> Result<()> {
+    if !self.follow_redirects && self.tracks_redirects() {
+      bail!("`follow_redirects` cannot be disabled along with `max_hops`, `final_url`, `final_host` or `no_downgrade`");
+    }
+
+    Ok(())
+  }
+
   pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Http> {
     let spec = sqlx::query_as::<_, Http>(
       "
-        SELECT id, check_id, url, timeout, headers, code, content, digest, json_query, duration
+        SELECT id, check_id, url, method, timeout, headers, body, username, password, token, client_cert, client_key, proxy, follow_redirects, verify_tls, max_hops, final_url, final_host, no_downgrade, code, codes, response_headers, content, regex, not_content, not_regex, digest, json_query, json_schema, duration
         FROM http_specs
         WHERE check_id = ?
       ",
@@ -61,18 +160,38 @@ impl Http {
   pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Http) -> Result<()> {
     sqlx::query(
       "
-        INSERT INTO http_specs ( check_id, url, headers, timeout, code, content, digest, json_query, duration )
-        VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, ? )
+        INSERT INTO http_specs ( check_id, url, method, headers, body, username, password, token, client_cert, client_key, proxy, follow_redirects, verify_tls, max_hops, final_url, final_host, no_downgrade, timeout, code, codes, response_headers, content, regex, not_content, not_regex, digest, json_query, json_schema, duration )
+        VALUES ( ?, ?, ?, ?, ?, ?, NULLIF(?, ''), NULLIF(?, ''), ?, NULLIF(?, ''), ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ? )
       ",
     )
     .bind(check.id)
     .bind(spec.url)
+    .bind(spec.method)
     .bind(spec.headers)
+    .bind(spec.body)
+    .bind(spec.username)
+    .bind(spec.password)
+    .bind(spec.token)
+    .bind(spec.client_cert)
+    .bind(spec.client_key)
+    .bind(spec.proxy)
+    .bind(spec.follow_redirects)
+    .bind(spec.verify_tls)
+    .bind(spec.max_hops)
+    .bind(spec.final_url)
+    .bind(spec.final_host)
+    .bind(spec.no_downgrade)
     .bind(spec.timeout)
     .bind(spec.code)
+    .bind(spec.codes)
+    .bind(spec.response_headers)
     .bind(spec.content)
+    .bind(spec.regex)
+    .bind(spec.not_content)
+    .bind(spec.not_regex)
     .bind(spec.digest)
     .bind(spec.json_query)
+    .bind(spec.json_schema)
     .bind(spec.duration)
     .execute(pool)
     .await?;
@@ -84,17 +203,39 @@ impl Http {
     sqlx::query(
       "
         UPDATE http_specs
-        SET url = ?, headers = ?, timeout = ?, code = ?, content = ?, digest = ?, json_query = ?, duration = ?
+        SET url = ?, method = ?, headers = ?, body = ?, username = ?, password = NULLIF(COALESCE(?, password), ''), token = NULLIF(COALESCE(?, token), ''), client_cert = ?,
+            client_key = NULLIF(COALESCE(?, client_key), ''), proxy = ?, follow_redirects = ?, verify_tls = ?, max_hops = ?, final_url = ?, final_host = ?, no_downgrade = ?, timeout = ?, code = ?, codes = ?, response_headers = ?, content = ?, regex = ?,
+            not_content = ?, not_regex = ?, digest = ?, json_query = ?, json_schema = ?, duration = ?
         WHERE check_id = ?
       ",
     )
     .bind(spec.url)
+    .bind(spec.method)
     .bind(spec.headers)
+    .bind(spec.body)
+    .bind(spec.username)
+    .bind(spec.password)
+    .bind(spec.token)
+    .bind(spec.client_cert)
+    .bind(spec.client_key)
+    .bind(spec.proxy)
+    .bind(spec.follow_redirects)
+    .bind(spec.verify_tls)
+    .bind(spec.max_hops)
+    .bind(spec.final_url)
+    .bind(spec.final_host)
+    .bind(spec.no_downgrade)
     .bind(spec.timeout)
     .bind(spec.code)
+    .bind(spec.codes)
+    .bind(spec.response_headers)
     .bind(spec.content)
+    .bind(spec.regex)
+    .bind(spec.not_content)
+    .bind(spec.not_regex)
     .bind(spec.digest)
     .bind(spec.json_query)
+    .bind(spec.json_schema)
     .bind(spec.duration)
     .bind(check.id)
     .execute(conn)
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use sqlx::{types::Json, FromRow, MySqlConnection};
+
+use crate::model::{
+  specs::{Http, SpecMeta},
+  Check,
+};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct HttpFlow {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub steps: Json<Vec<HttpFlowStep>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpFlowStep {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  #[serde(flatten)]
+  pub request: Http,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub extract: Vec<HttpExtraction>,
+}
+
+/// Variable to extract from a response, from exactly one of a JSON pointer, a regex or a header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpExtraction {
+  pub variable: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub json: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub regex: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub header: Option<String>,
+}
+
+impl SpecMeta for HttpFlow {
+  fn name(&self) -> &'static str {
+    "HTTP flow"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    let steps = self.steps.iter().map(|step| step.name.clone().unwrap_or_else(|| step.request.url.clone())).collect::<Vec<_>>();
+
+    vec![("Steps", steps.join(", "))]
+  }
+}
+
+impl HttpFlow {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<HttpFlow> {
+    let spec = sqlx::query_as::<_, HttpFlow>(
+      "
+        SELECT id, check_id, steps
+        FROM http_flow_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, mut spec: HttpFlow) -> Result<()> {
+    spec.clear_empty_secrets();
+
+    sqlx::query(
+      "
+        INSERT INTO http_flow_specs ( check_id, steps )
+        VALUES ( ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.steps)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, mut spec: HttpFlow) -> Result<()> {
+    // Secrets are redacted from API responses, so steps sent back without them keep the ones stored for the same step and URL.
+    if let Ok(current) = HttpFlow::for_check(&mut *conn, check).await {
+      for (step, stored) in spec.steps.0.iter_mut().zip(current.steps.0) {
+        if step.request.url == stored.request.url {
+          step.request.password = step.request.password.take().or(stored.request.password);
+          step.request.token = step.request.token.take().or(stored.request.token);
+          step.request.client_key = step.request.client_key.take().or(stored.request.client_key);
+        }
+      }
+    }
+
+    spec.clear_empty_secrets();
+
+    sqlx::query(
+      "
+        UPDATE http_flow_specs
+        SET steps = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.steps)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+
+  /// Remove secrets given as empty strings, which is how they are cleared from a step.
+  fn clear_empty_secrets(&mut self) {
+    for step in self.steps.0.iter_mut() {
+      for secret in [&mut step.request.password, &mut step.request.token, &mut step.request.client_key] {
+        if secret.as_deref() == Some("") {
+          *secret = None;
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,4 @@
+mod protocol;
+mod spec;
+
+pub use self::spec::*;
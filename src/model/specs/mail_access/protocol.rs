@@ -0,0 +1,128 @@
+use std::{
+  convert::TryFrom,
+  error::Error,
+  fmt::{self, Display, Formatter},
+};
+
+use sqlx::{
+  encode::IsNull,
+  error::BoxDynError,
+  mysql::{MySqlTypeInfo, MySqlValueRef},
+  types::Type,
+  Decode, Encode, MySql,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MailProtocol {
+  #[default]
+  Imap,
+  Pop3,
+}
+
+impl Display for MailProtocol {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+    use MailProtocol::*;
+
+    let name = match self {
+      Imap => "imap",
+      Pop3 => "pop3",
+    };
+
+    write!(formatter, "{name}")
+  }
+}
+
+impl TryFrom<String> for MailProtocol {
+  type Error = anyhow::Error;
+
+  fn try_from(protocol: String) -> Result<MailProtocol, Self::Error> {
+    use MailProtocol::*;
+
+    match protocol.as_str() {
+      "imap" => Ok(Imap),
+      "pop3" => Ok(Pop3),
+      _ => Err(anyhow!("invalid value for protocol")),
+    }
+  }
+}
+
+impl Type<MySql> for MailProtocol {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for MailProtocol {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(self.to_string(), buf)
+  }
+}
+
+impl Decode<'_, MySql> for MailProtocol {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(MailProtocol::try_from(<&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)?)?)
+  }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MailSecurity {
+  Plain,
+  #[default]
+  Tls,
+  StartTls,
+}
+
+impl Display for MailSecurity {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+    use MailSecurity::*;
+
+    let name = match self {
+      Plain => "plain",
+      Tls => "tls",
+      StartTls => "starttls",
+    };
+
+    write!(formatter, "{name}")
+  }
+}
+
+impl TryFrom<String> for MailSecurity {
+  type Error = anyhow::Error;
+
+  fn try_from(security: String) -> Result<MailSecurity, Self::Error> {
+    use MailSecurity::*;
+
+    match security.as_str() {
+      "plain" => Ok(Plain),
+      "tls" => Ok(Tls),
+      "starttls" => Ok(StartTls),
+      _ => Err(anyhow!("invalid value for security")),
+    }
+  }
+}
+
+impl Type<MySql> for MailSecurity {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for MailSecurity {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(self.to_string(), buf)
+  }
+}
+
+impl Decode<'_, MySql> for MailSecurity {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(MailSecurity::try_from(<&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)?)?)
+  }
+}
@@ -0,0 +1,127 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration};
+
+pub use super::protocol::*;
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct MailAccess {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  #[serde(default)]
+  pub protocol: MailProtocol,
+  pub host: String,
+  pub port: u16,
+  #[serde(default)]
+  pub security: MailSecurity,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub username: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub password: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub mailbox: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub min_messages: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_messages: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for MailAccess {
+  fn name(&self) -> &'static str {
+    "Mailbox access"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("Protocol", self.protocol.to_string().to_uppercase()), ("Host", self.host.clone()), ("Port", self.port.to_string())];
+
+    if let Some(ref username) = self.username {
+      fields.push(("Username", username.clone()));
+    }
+
+    if let Some(ref mailbox) = self.mailbox {
+      fields.push(("Mailbox", mailbox.clone()));
+    }
+
+    fields
+  }
+}
+
+impl MailAccess {
+  /// Reject message count assertions without credentials, since only a logged in session can count messages.
+  pub fn validate(&self) -> Result<()> {
+    if (self.min_messages.is_some() || self.max_messages.is_some()) && self.username.is_none() {
+      bail!("`min_messages` and `max_messages` require a `username`");
+    }
+
+    Ok(())
+  }
+
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<MailAccess> {
+    let spec = sqlx::query_as::<_, MailAccess>(
+      "
+        SELECT id, check_id, protocol, host, port, security, username, password, mailbox, min_messages, max_messages, timeout
+        FROM mail_access_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: MailAccess) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO mail_access_specs ( check_id, protocol, host, port, security, username, password, mailbox, min_messages, max_messages, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ?, NULLIF(?, ''), ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.protocol)
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.security)
+    .bind(spec.username)
+    .bind(spec.password)
+    .bind(spec.mailbox)
+    .bind(spec.min_messages)
+    .bind(spec.max_messages)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: MailAccess) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE mail_access_specs
+        SET protocol = ?, host = ?, port = ?, security = ?, username = ?, password = NULLIF(COALESCE(?, password), ''), mailbox = ?, min_messages = ?, max_messages = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.protocol)
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.security)
+    .bind(spec.username)
+    .bind(spec.password)
+    .bind(spec.mailbox)
+    .bind(spec.min_messages)
+    .bind(spec.max_messages)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
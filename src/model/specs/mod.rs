@@ -1,32 +1,68 @@
 mod app_store;
+mod crawl;
+mod database;
 mod deadmanswitch;
 mod dns;
+mod dns_consistency;
+mod dnssec;
+mod exec;
+mod external;
+mod grpc;
 mod http;
+mod http_flow;
+mod mail_access;
+mod ntp;
 #[cfg(feature = "ping")]
 mod ping;
 mod play_store;
+mod prometheus;
 #[cfg(feature = "python")]
 mod python;
+mod script;
+mod security_headers;
+mod smtp;
+mod ssh;
 mod tcp;
 mod tls;
 mod udp;
 mod unsupported;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod websocket;
 mod whois;
 
 #[cfg(feature = "ping")]
 pub use self::ping::Ping;
 #[cfg(feature = "python")]
 pub use self::python::Python;
+#[cfg(feature = "wasm")]
+pub use self::wasm::Wasm;
 pub use self::{
   app_store::AppStore,
+  crawl::Crawl,
+  database::{Database, DatabaseEngine},
   deadmanswitch::DeadManSwitch,
-  dns::{Dns, DnsRecord},
-  http::{Http, HttpHeaders},
+  dns::{Dns, DnsMatching, DnsRecord, DnsTransport},
+  dns_consistency::DnsConsistency,
+  dnssec::Dnssec,
+  exec::Exec,
+  external::External,
+  grpc::Grpc,
+  http::{Http, HttpHeaders, HttpMethod},
+  http_flow::{HttpExtraction, HttpFlow, HttpFlowStep},
+  mail_access::{MailAccess, MailProtocol, MailSecurity},
+  ntp::Ntp,
   play_store::PlayStore,
+  prometheus::Prometheus,
+  script::Script,
+  security_headers::SecurityHeaders,
+  smtp::Smtp,
+  ssh::Ssh,
   tcp::Tcp,
-  tls::Tls,
+  tls::{StartTlsProtocol, Tls},
   udp::Udp,
   unsupported::Unsupported,
+  websocket::WebSocket,
   whois::Whois,
 };
 
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct Ntp {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub host: String,
+  #[serde(default = "default_port")]
+  pub port: u16,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub warning_ms: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub critical_ms: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+const fn default_port() -> u16 {
+  123
+}
+
+impl SpecMeta for Ntp {
+  fn name(&self) -> &'static str {
+    "NTP clock offset"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("Host", self.host.clone()), ("Port", self.port.to_string())]
+  }
+}
+
+impl Ntp {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Ntp> {
+    let spec = sqlx::query_as::<_, Ntp>(
+      "
+        SELECT id, check_id, host, port, warning_ms, critical_ms, timeout
+        FROM ntp_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Ntp) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO ntp_specs ( check_id, host, port, warning_ms, critical_ms, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.warning_ms)
+    .bind(spec.critical_ms)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Ntp) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE ntp_specs
+        SET host = ?, port = ?, warning_ms = ?, critical_ms = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.warning_ms)
+    .bind(spec.critical_ms)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::{types::Json, FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration};
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct Prometheus {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub url: String,
+  pub metric: String,
+  #[serde(default)]
+  pub labels: Json<HashMap<String, String>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub warning: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub critical: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Prometheus {
+  fn name(&self) -> &'static str {
+    "Prometheus metric"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("URL", self.url.clone()), ("Metric", self.metric.clone())]
+  }
+}
+
+impl Prometheus {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Prometheus> {
+    let spec = sqlx::query_as::<_, Prometheus>(
+      "
+        SELECT id, check_id, url, metric, labels, warning, critical, timeout
+        FROM prometheus_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Prometheus) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO prometheus_specs ( check_id, url, metric, labels, warning, critical, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.url)
+    .bind(spec.metric)
+    .bind(spec.labels)
+    .bind(spec.warning)
+    .bind(spec.critical)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Prometheus) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE prometheus_specs
+        SET url = ?, metric = ?, labels = ?, warning = ?, critical = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.url)
+    .bind(spec.metric)
+    .bind(spec.labels)
+    .bind(spec.warning)
+    .bind(spec.critical)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
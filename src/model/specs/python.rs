@@ -1,15 +1,20 @@
 use anyhow::Result;
-use sqlx::{FromRow, MySqlConnection};
+use serde_json::{Map, Value};
+use sqlx::{types::Json, FromRow, MySqlConnection};
 
-use crate::model::{specs::SpecMeta, Check};
+use crate::model::{specs::SpecMeta, Check, Duration};
 
-#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
 pub struct Python {
   #[serde(skip)]
   pub id: u64,
   #[serde(skip)]
   pub check_id: u64,
   pub script: String,
+  #[serde(default)]
+  pub params: Json<Map<String, Value>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
 }
 
 impl SpecMeta for Python {
@@ -26,7 +31,7 @@ impl Python {
   pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Python> {
     let spec = sqlx::query_as::<_, Python>(
       "
-        SELECT id, check_id, script
+        SELECT id, check_id, script, params, timeout
         FROM python_specs
         WHERE check_id = ?
       ",
@@ -41,12 +46,14 @@ impl Python {
   pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Python) -> Result<()> {
     sqlx::query(
       "
-        INSERT INTO python_specs ( check_id, script )
-        VALUES ( ?, ? )
+        INSERT INTO python_specs ( check_id, script, params, timeout )
+        VALUES ( ?, ?, ?, ? )
       ",
     )
     .bind(check.id)
     .bind(spec.script)
+    .bind(spec.params)
+    .bind(spec.timeout)
     .execute(pool)
     .await?;
 
@@ -57,11 +64,13 @@ impl Python {
     sqlx::query(
       "
         UPDATE python_specs
-        SET script = ?
+        SET script = ?, params = ?, timeout = ?
         WHERE check_id = ?
       ",
     )
     .bind(spec.script)
+    .bind(spec.params)
+    .bind(spec.timeout)
     .bind(check.id)
     .execute(conn)
     .await?;
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration};
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct Script {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub code: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Script {
+  fn name(&self) -> &'static str {
+    "Rhai script"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("Lines", self.code.lines().count().to_string())]
+  }
+}
+
+impl Script {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Script> {
+    let spec = sqlx::query_as::<_, Script>(
+      "
+        SELECT id, check_id, code, timeout
+        FROM script_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Script) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO script_specs ( check_id, code, timeout )
+        VALUES ( ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.code)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Script) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE script_specs
+        SET code = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.code)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration, StringList};
+
+/// Names of the audits performed by the handler, which can be made mandatory.
+pub const AUDITS: [&str; 6] = ["hsts", "csp", "content_type_options", "frame_options", "cookies", "https_redirect"];
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct SecurityHeaders {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub url: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub hsts_max_age: Option<Duration>,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub mandatory: StringList,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for SecurityHeaders {
+  fn name(&self) -> &'static str {
+    "Security headers"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("URL", self.url.clone())]
+  }
+}
+
+impl SecurityHeaders {
+  /// Reject mandatory audits which do not exist, since a typo would silently turn failures into warnings.
+  pub fn validate(&self) -> Result<()> {
+    if let Some(audit) = self.mandatory.iter().find(|audit| !AUDITS.contains(&audit.as_str())) {
+      bail!("unknown audit `{audit}` in `mandatory`, expected one of {}", AUDITS.join(", "));
+    }
+
+    Ok(())
+  }
+
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<SecurityHeaders> {
+    let spec = sqlx::query_as::<_, SecurityHeaders>(
+      "
+        SELECT id, check_id, url, hsts_max_age, mandatory, timeout
+        FROM security_headers_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: SecurityHeaders) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO security_headers_specs ( check_id, url, hsts_max_age, mandatory, timeout )
+        VALUES ( ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.url)
+    .bind(spec.hsts_max_age)
+    .bind(spec.mandatory)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: SecurityHeaders) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE security_headers_specs
+        SET url = ?, hsts_max_age = ?, mandatory = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.url)
+    .bind(spec.hsts_max_age)
+    .bind(spec.mandatory)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
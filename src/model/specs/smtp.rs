@@ -0,0 +1,95 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::{
+  ext,
+  model::{specs::SpecMeta, Check, Duration, StringList},
+};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct Smtp {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub host: String,
+  pub port: u16,
+  #[serde(default = "ext::to_false")]
+  pub starttls: bool,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub extensions: StringList,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub auth_mechanisms: StringList,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Smtp {
+  fn name(&self) -> &'static str {
+    "SMTP server"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("Host", self.host.clone()), ("Port", self.port.to_string())]
+  }
+}
+
+impl Smtp {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Smtp> {
+    let spec = sqlx::query_as::<_, Smtp>(
+      "
+        SELECT id, check_id, host, port, starttls, extensions, auth_mechanisms, timeout
+        FROM smtp_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Smtp) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO smtp_specs ( check_id, host, port, starttls, extensions, auth_mechanisms, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.starttls)
+    .bind(spec.extensions)
+    .bind(spec.auth_mechanisms)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Smtp) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE smtp_specs
+        SET host = ?, port = ?, starttls = ?, extensions = ?, auth_mechanisms = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.starttls)
+    .bind(spec.extensions)
+    .bind(spec.auth_mechanisms)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Check, Duration};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct Ssh {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub host: String,
+  #[serde(default = "default_port")]
+  pub port: u16,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub algorithm: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fingerprint: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+const fn default_port() -> u16 {
+  22
+}
+
+impl SpecMeta for Ssh {
+  fn name(&self) -> &'static str {
+    "SSH host key"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("Host", self.host.clone()), ("Port", self.port.to_string())];
+
+    if let Some(ref fingerprint) = self.fingerprint {
+      fields.push(("Fingerprint", fingerprint.clone()));
+    }
+
+    fields
+  }
+}
+
+impl Ssh {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Ssh> {
+    let spec = sqlx::query_as::<_, Ssh>(
+      "
+        SELECT id, check_id, host, port, algorithm, fingerprint, timeout
+        FROM ssh_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Ssh) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO ssh_specs ( check_id, host, port, algorithm, fingerprint, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.algorithm)
+    .bind(spec.fingerprint)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Ssh) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE ssh_specs
+        SET host = ?, port = ?, algorithm = ?, fingerprint = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.host)
+    .bind(spec.port)
+    .bind(spec.algorithm)
+    .bind(spec.fingerprint)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,77 @@
+use std::{
+  convert::TryFrom,
+  error::Error,
+  fmt::{self, Display, Formatter},
+};
+
+use sqlx::{
+  encode::IsNull,
+  error::BoxDynError,
+  mysql::{MySqlTypeInfo, MySqlValueRef},
+  types::Type,
+  Decode, Encode, MySql,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartTlsProtocol {
+  Smtp,
+  Imap,
+  Ldap,
+  Postgres,
+  Xmpp,
+}
+
+impl Display for StartTlsProtocol {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+    use StartTlsProtocol::*;
+
+    let name = match self {
+      Smtp => "smtp",
+      Imap => "imap",
+      Ldap => "ldap",
+      Postgres => "postgres",
+      Xmpp => "xmpp",
+    };
+
+    write!(formatter, "{name}")
+  }
+}
+
+impl TryFrom<String> for StartTlsProtocol {
+  type Error = anyhow::Error;
+
+  fn try_from(protocol: String) -> Result<StartTlsProtocol, Self::Error> {
+    use StartTlsProtocol::*;
+
+    match protocol.as_str() {
+      "smtp" => Ok(Smtp),
+      "imap" => Ok(Imap),
+      "ldap" => Ok(Ldap),
+      "postgres" => Ok(Postgres),
+      "xmpp" => Ok(Xmpp),
+      _ => Err(anyhow!("invalid value for starttls")),
+    }
+  }
+}
+
+impl Type<MySql> for StartTlsProtocol {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for StartTlsProtocol {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(self.to_string(), buf)
+  }
+}
+
+impl Decode<'_, MySql> for StartTlsProtocol {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(StartTlsProtocol::try_from(<&str as Decode<MySql>>::decode(value).map(ToOwned::to_owned)?)?)
+  }
+}
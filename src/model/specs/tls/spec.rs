@@ -0,0 +1,134 @@
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::{
+  ext,
+  model::{
+    specs::{SpecMeta, StartTlsProtocol},
+    Check, Duration,
+  },
+};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct Tls {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub domain: String,
+  #[serde(default = "default_port")]
+  pub port: u16,
+  pub window: Duration,
+  #[serde(default = "ext::to_false")]
+  pub verify_hostname: bool,
+  #[serde(default = "ext::to_false")]
+  pub verify_chain: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub issuer: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub min_key_size: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub signature_algorithm: Option<String>,
+  #[serde(default = "ext::to_false")]
+  pub ocsp: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fingerprint: Option<String>,
+  #[serde(default = "ext::to_false")]
+  pub learn: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub starttls: Option<StartTlsProtocol>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+const fn default_port() -> u16 {
+  443
+}
+
+impl SpecMeta for Tls {
+  fn name(&self) -> &'static str {
+    "TLS certificate"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("Domain", self.domain.clone()), ("Port", self.port.to_string())];
+
+    if let Some(ref fingerprint) = self.fingerprint {
+      fields.push(("Fingerprint", fingerprint.clone()));
+    }
+
+    fields
+  }
+}
+
+impl Tls {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Tls> {
+    let spec = sqlx::query_as::<_, Tls>(
+      "
+        SELECT id, check_id, domain, port, window, verify_hostname, verify_chain, issuer, min_key_size, signature_algorithm, ocsp, fingerprint, learn, starttls, timeout
+        FROM tls_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Tls) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO tls_specs ( check_id, domain, port, window, verify_hostname, verify_chain, issuer, min_key_size, signature_algorithm, ocsp, fingerprint, learn, starttls, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.domain)
+    .bind(spec.port)
+    .bind(spec.window)
+    .bind(spec.verify_hostname)
+    .bind(spec.verify_chain)
+    .bind(spec.issuer)
+    .bind(spec.min_key_size)
+    .bind(spec.signature_algorithm)
+    .bind(spec.ocsp)
+    .bind(spec.fingerprint)
+    .bind(spec.learn)
+    .bind(spec.starttls)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Tls) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE tls_specs
+        SET domain = ?, port = ?, window = ?, verify_hostname = ?, verify_chain = ?, issuer = ?, min_key_size = ?, signature_algorithm = ?, ocsp = ?, fingerprint = ?, learn = ?, starttls = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.domain)
+    .bind(spec.port)
+    .bind(spec.window)
+    .bind(spec.verify_hostname)
+    .bind(spec.verify_chain)
+    .bind(spec.issuer)
+    .bind(spec.min_key_size)
+    .bind(spec.signature_algorithm)
+    .bind(spec.ocsp)
+    .bind(spec.fingerprint)
+    .bind(spec.learn)
+    .bind(spec.starttls)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
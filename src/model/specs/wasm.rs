@@ -0,0 +1,96 @@
+use anyhow::Result;
+use sqlx::{types::Json, FromRow, MySqlConnection};
+
+use crate::model::{specs::SpecMeta, Binary, Check, Duration};
+
+#[derive(Debug, Default, FromRow, Clone, Serialize, Deserialize)]
+pub struct Wasm {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub plugin: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub module: Option<Binary>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub config: Option<Json<serde_json::Value>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fuel: Option<u64>,
+  /// Maximum size of the linear memory of the plugin, in MiB.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub memory: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for Wasm {
+  fn name(&self) -> &'static str {
+    "WebAssembly plugin"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    match self.plugin {
+      Some(ref plugin) => vec![("Plugin", plugin.clone())],
+      None => vec![("Plugin", "<inline module>".to_string())],
+    }
+  }
+}
+
+impl Wasm {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<Wasm> {
+    let spec = sqlx::query_as::<_, Wasm>(
+      "
+        SELECT id, check_id, plugin, module, config, fuel, memory, timeout
+        FROM wasm_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: Wasm) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO wasm_specs ( check_id, plugin, module, config, fuel, memory, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.plugin)
+    .bind(spec.module)
+    .bind(spec.config)
+    .bind(spec.fuel)
+    .bind(spec.memory)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: Wasm) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE wasm_specs
+        SET plugin = ?, module = ?, config = ?, fuel = ?, memory = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.plugin)
+    .bind(spec.module)
+    .bind(spec.config)
+    .bind(spec.fuel)
+    .bind(spec.memory)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
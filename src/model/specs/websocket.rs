@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::{FromRow, MySqlConnection};
+
+use crate::model::{
+  specs::{http::HttpHeaders, SpecMeta},
+  Check, Duration,
+};
+
+#[derive(Debug, FromRow, Clone, Serialize, Deserialize)]
+pub struct WebSocket {
+  #[serde(skip)]
+  pub id: u64,
+  #[serde(skip)]
+  pub check_id: u64,
+  pub url: String,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  pub headers: HttpHeaders,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub message: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub content: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<Duration>,
+}
+
+impl SpecMeta for WebSocket {
+  fn name(&self) -> &'static str {
+    "WebSocket"
+  }
+
+  fn fields(&self) -> Vec<(&'static str, String)> {
+    vec![("URL", self.url.clone())]
+  }
+}
+
+impl WebSocket {
+  pub async fn for_check(conn: &mut MySqlConnection, check: &Check) -> Result<WebSocket> {
+    let spec = sqlx::query_as::<_, WebSocket>(
+      "
+        SELECT id, check_id, url, headers, message, content, timeout
+        FROM websocket_specs
+        WHERE check_id = ?
+      ",
+    )
+    .bind(check.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(spec)
+  }
+
+  pub async fn insert(pool: &mut MySqlConnection, check: &Check, spec: WebSocket) -> Result<()> {
+    sqlx::query(
+      "
+        INSERT INTO websocket_specs ( check_id, url, headers, message, content, timeout )
+        VALUES ( ?, ?, ?, ?, ?, ? )
+      ",
+    )
+    .bind(check.id)
+    .bind(spec.url)
+    .bind(spec.headers)
+    .bind(spec.message)
+    .bind(spec.content)
+    .bind(spec.timeout)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+  }
+
+  pub async fn update(conn: &mut MySqlConnection, check: &Check, spec: WebSocket) -> Result<()> {
+    sqlx::query(
+      "
+        UPDATE websocket_specs
+        SET url = ?, headers = ?, message = ?, content = ?, timeout = ?
+        WHERE check_id = ?
+      ",
+    )
+    .bind(spec.url)
+    .bind(spec.headers)
+    .bind(spec.message)
+    .bind(spec.content)
+    .bind(spec.timeout)
+    .bind(check.id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+  }
+}
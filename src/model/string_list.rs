@@ -0,0 +1,48 @@
+use std::{error::Error, ops::Deref};
+
+use sqlx::{
+  encode::IsNull,
+  error::BoxDynError,
+  mysql::{MySqlTypeInfo, MySqlValueRef},
+  types::Type,
+  Decode, Encode, MySql,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringList(pub Vec<String>);
+
+impl Deref for StringList {
+  type Target = Vec<String>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl From<Vec<String>> for StringList {
+  fn from(items: Vec<String>) -> StringList {
+    StringList(items)
+  }
+}
+
+impl Type<MySql> for StringList {
+  fn type_info() -> MySqlTypeInfo {
+    <str as Type<MySql>>::type_info()
+  }
+
+  fn compatible(ty: &MySqlTypeInfo) -> bool {
+    <str as Type<MySql>>::compatible(ty)
+  }
+}
+
+impl Encode<'_, MySql> for StringList {
+  fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+    <String as sqlx::Encode<MySql>>::encode(serde_json::to_string(&self)?, buf)
+  }
+}
+
+impl Decode<'_, MySql> for StringList {
+  fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+    Ok(serde_json::from_str(<&str as Decode<MySql>>::decode(value)?)?)
+  }
+}
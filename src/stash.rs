@@ -47,6 +47,28 @@ impl Stash {
   pub async fn delete(&mut self, check: &Check, key: &str) {
     self.write().await.remove(&format!("{}-{key}", check.uuid));
   }
+
+  /// List every entry stashed for a check, keyed without the check prefix.
+  pub async fn entries(&self, check: &Check) -> HashMap<String, String> {
+    let prefix = format!("{}-", check.uuid);
+
+    self
+      .read()
+      .await
+      .iter()
+      .filter_map(|(key, value)| key.strip_prefix(&prefix).map(|key| (key.to_owned(), value.to_owned())))
+      .collect()
+  }
+
+  /// Same as `stash()`, for use outside of the async runtime, such as within blocking tasks.
+  pub fn blocking_stash(&mut self, check: &Check, key: &str, value: &str) {
+    self.blocking_write().insert(format!("{}-{key}", check.uuid), value.to_owned());
+  }
+
+  /// Same as `retrieve()`, for use outside of the async runtime, such as within blocking tasks.
+  pub fn blocking_retrieve(&self, check: &Check, key: &str) -> Option<String> {
+    self.blocking_read().get(&format!("{}-{key}", check.uuid)).map(ToOwned::to_owned)
+  }
 }
 
 #[cfg(test)]
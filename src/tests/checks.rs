@@ -49,4 +49,17 @@ impl TestConnection {
 
     Ok(())
   }
+
+  /// Turn the check into a database check, with a password in its DSN.
+  pub async fn create_database_spec(&self, id: u64) -> Result<()> {
+    sqlx::query(r#"UPDATE checks SET kind = "database" WHERE id = ?"#).bind(id).execute(&**self).await?;
+    sqlx::query(r#"DELETE FROM tcp_specs WHERE check_id = ?"#).bind(id).execute(&**self).await?;
+
+    sqlx::query(r#"INSERT INTO database_specs (check_id, engine, dsn) VALUES ( ?, "redis", "redis://:secret@localhost/" )"#)
+      .bind(id)
+      .execute(&**self)
+      .await?;
+
+    Ok(())
+  }
 }
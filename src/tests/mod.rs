@@ -60,6 +60,9 @@ pub fn config(auth: bool) -> Arc<Config> {
     },
     checks: ChecksConfig {
       dns_resolver: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+      plugins_path: "/tmp".to_string(),
+      handlers_path: "/tmp".to_string(),
+      handlers: vec![],
       #[cfg(feature = "python")]
       scripts_path: "/tmp".to_string(),
     },